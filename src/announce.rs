@@ -0,0 +1,89 @@
+use std::{
+    cell::RefCell,
+    thread::LocalKey,
+};
+use gloo_utils::document;
+use wasm_bindgen::JsValue;
+use web_sys::Element;
+use crate::{
+    el,
+    schedule::timeout,
+    style,
+    El,
+    Position,
+    Root,
+    ScopeValue,
+};
+
+struct LiveRegion {
+    el: El,
+    pending: Option<ScopeValue>,
+    _root: Root,
+}
+
+thread_local!{
+    static POLITE: RefCell<Option<LiveRegion>> = const {
+        RefCell::new(None)
+    };
+    static ASSERTIVE: RefCell<Option<LiveRegion>> = const {
+        RefCell::new(None)
+    };
+}
+
+fn new_live_region(politeness: &str) -> LiveRegion {
+    let region =
+        el("div")
+            .attr("aria-live", politeness)
+            .attr("aria-atomic", "true")
+            .style(
+                style()
+                    .position(Position::Absolute)
+                    .width_px(1.)
+                    .height_px(1.)
+                    .prop("overflow", "hidden")
+                    .prop("clip", "rect(0 0 0 0)")
+                    .prop("clip-path", "inset(50%)")
+                    .prop("white-space", "nowrap"),
+            );
+    let body = document().body().map(Element::from).ok_or_else(|| JsValue::from_str("Document has no body"));
+    return LiveRegion {
+        _root: Root::append_at(body, vec![region.clone()]),
+        el: region,
+        pending: None,
+    };
+}
+
+fn announce(
+    region: &'static LocalKey<RefCell<Option<LiveRegion>>>,
+    politeness: &str,
+    message: &str,
+) {
+    region.with(|r| {
+        let mut r = r.borrow_mut();
+        let r = r.get_or_insert_with(|| new_live_region(politeness));
+
+        // Clear then set the text after a moment so screen readers notice repeated
+        // messages
+        r.el.ref_text("");
+        let e = r.el.weak();
+        let message = message.to_string();
+        r.pending = Some(timeout(50, move || {
+            let Some(e) = e.upgrade() else {
+                return;
+            };
+            e.ref_text(&message);
+        }));
+    });
+}
+
+/// Have screen readers read `message` when the user is idle, via a visually hidden
+/// `aria-live="polite"` region created on first use.
+pub fn announce_polite(message: &str) {
+    announce(&POLITE, "polite", message);
+}
+
+/// Have screen readers read `message` immediately, interrupting what they're
+/// reading.  Use this sparingly, for urgent messages like errors.
+pub fn announce_assertive(message: &str) {
+    announce(&ASSERTIVE, "assertive", message);
+}
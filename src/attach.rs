@@ -0,0 +1,160 @@
+use std::{
+    cell::RefCell,
+    rc::Rc,
+};
+use gloo_utils::document;
+use js_sys::Array;
+use wasm_bindgen::{
+    prelude::Closure,
+    JsCast,
+    JsValue,
+};
+use web_sys::{
+    Document,
+    Element,
+    MutationObserver,
+    MutationObserverInit,
+};
+use crate::{
+    defer,
+    scope_any,
+    error::ReportExt,
+    El,
+    ScopeValue,
+};
+
+type AttachCb = Rc<RefCell<dyn FnMut(bool)>>;
+
+struct Watch {
+    id: usize,
+    el: Element,
+    connected: bool,
+    cb: AttachCb,
+}
+
+/// A single `MutationObserver` shared by all attach/detach watches, only running
+/// while there are watches.  It observes the documents of all watched elements.
+#[derive(Default)]
+struct Watches {
+    next_id: usize,
+    watches: Vec<Watch>,
+    observer: Option<(MutationObserver, ScopeValue)>,
+    observed: Vec<Document>,
+}
+
+thread_local!{
+    static WATCHES: RefCell<Watches> = RefCell::new(Watches::default());
+}
+
+fn check_watches() {
+    let fire = WATCHES.with(|w| {
+        let mut w = w.borrow_mut();
+        let mut fire = vec![];
+        for watch in &mut w.watches {
+            let connected = watch.el.is_connected();
+            if connected != watch.connected {
+                watch.connected = connected;
+                fire.push((watch.cb.clone(), connected));
+            }
+        }
+        return fire;
+    });
+    for (cb, connected) in fire {
+        (cb.borrow_mut())(connected);
+    }
+}
+
+pub(crate) fn watch_connected(el: &Element, cb: impl FnMut(bool) + 'static) -> ScopeValue {
+    let id = WATCHES.with(|w| {
+        let mut w = w.borrow_mut();
+        let id = w.next_id;
+        w.next_id += 1;
+        w.watches.push(Watch {
+            id,
+            el: el.clone(),
+            connected: el.is_connected(),
+            cb: Rc::new(RefCell::new(cb)),
+        });
+        if w.observer.is_none() {
+            let js_cb = Closure::wrap(Box::new(|_: Array, _: JsValue| {
+                check_watches();
+            }) as Box<dyn Fn(Array, JsValue)>);
+            if let Some(observer) = MutationObserver::new(js_cb.as_ref().unchecked_ref()).or_report() {
+                w.observer = Some((observer, scope_any(js_cb)));
+            }
+        }
+        let doc = el.owner_document().unwrap_or_else(document);
+        if !w.observed.contains(&doc) {
+            if let Some((observer, _)) = &w.observer {
+                let opts = MutationObserverInit::new();
+                opts.set_child_list(true);
+                opts.set_subtree(true);
+                observer.observe_with_options(&doc, &opts).or_report();
+            }
+            w.observed.push(doc);
+        }
+        return id;
+    });
+    return defer(move || {
+        let observer = WATCHES.with(|w| {
+            let mut w = w.borrow_mut();
+            w.watches.retain(|watch| watch.id != id);
+            if w.watches.is_empty() {
+                w.observed.clear();
+                return w.observer.take();
+            }
+            return None;
+        });
+        if let Some((observer, _js_cb)) = observer {
+            observer.disconnect();
+        }
+    });
+}
+
+impl El {
+    /// Call `cb` when the element becomes connected to the document.  This is
+    /// detected with a shared `MutationObserver`, so it's called asynchronously after
+    /// the change.
+    pub fn on_attach(self, cb: impl FnMut(&El) + 'static) -> Self {
+        self.ref_on_attach(cb);
+        return self;
+    }
+
+    pub fn ref_on_attach(&self, mut cb: impl FnMut(&El) + 'static) -> &Self {
+        return self.ref_own(|e| {
+            let raw = e.raw();
+            let e = e.weak();
+            return watch_connected(&raw, move |connected| {
+                let Some(e) = e.upgrade() else {
+                    return;
+                };
+                if connected {
+                    cb(&e);
+                }
+            });
+        });
+    }
+
+    /// Call `cb` when the element is disconnected from the document.  Like
+    /// `on_attach` this is asynchronous, and won't be called if the element is
+    /// dropped when it's removed.
+    pub fn on_detach(self, cb: impl FnMut(&El) + 'static) -> Self {
+        self.ref_on_detach(cb);
+        return self;
+    }
+
+    pub fn ref_on_detach(&self, mut cb: impl FnMut(&El) + 'static) -> &Self {
+        return self.ref_own(|e| {
+            let raw = e.raw();
+            let e = e.weak();
+            return watch_connected(&raw, move |connected| {
+                let Some(e) = e.upgrade() else {
+                    return;
+                };
+                if !connected {
+                    cb(&e);
+                }
+            });
+        });
+    }
+}
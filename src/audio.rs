@@ -0,0 +1,82 @@
+use std::{
+    cell::RefCell,
+    rc::Rc,
+};
+use gloo_events::EventListener;
+use gloo_utils::document;
+use wasm_bindgen::JsValue;
+use web_sys::{
+    AudioContext,
+    AudioContextState,
+};
+use crate::error::ReportExt;
+
+/// An `AudioContext` that's closed when dropped, releasing the audio hardware.
+/// Attach it to the component that plays sound with `.own()`.
+///
+/// Browsers start audio contexts suspended until there's a user gesture, see
+/// `resume_on_user_gesture`.
+pub struct RootedAudioContext {
+    context: AudioContext,
+    gesture_listeners: Rc<RefCell<Vec<EventListener>>>,
+}
+
+impl RootedAudioContext {
+    pub fn new() -> Result<Self, JsValue> {
+        return Ok(RootedAudioContext {
+            context: AudioContext::new()?,
+            gesture_listeners: Rc::new(RefCell::new(vec![])),
+        });
+    }
+
+    pub fn raw(&self) -> &AudioContext {
+        return &self.context;
+    }
+
+    pub fn is_running(&self) -> bool {
+        return self.context.state() == AudioContextState::Running;
+    }
+
+    /// Start or restart processing.  This only works during or after a user gesture.
+    pub fn resume(&self) {
+        self.context.resume().or_report();
+    }
+
+    /// Pause processing (ex: while the component is hidden) without closing the
+    /// context.
+    pub fn suspend(&self) {
+        self.context.suspend().or_report();
+    }
+
+    /// Resume the context at the next user gesture (click, tap, or key press)
+    /// anywhere on the page, if it isn't already running.  The listeners are removed
+    /// after the first gesture.
+    pub fn resume_on_user_gesture(&self) {
+        if self.is_running() {
+            return;
+        }
+        let mut listeners = self.gesture_listeners.borrow_mut();
+        listeners.clear();
+        for event in ["pointerdown", "keydown", "touchend"] {
+            listeners.push(EventListener::new(&document(), event, {
+                let context = self.context.clone();
+                let listeners = Rc::downgrade(&self.gesture_listeners);
+                move |_| {
+                    context.resume().or_report();
+                    if let Some(listeners) = listeners.upgrade() {
+                        listeners.borrow_mut().clear();
+                    }
+                }
+            }));
+        }
+    }
+}
+
+impl Drop for RootedAudioContext {
+    fn drop(&mut self) {
+        self.gesture_listeners.borrow_mut().clear();
+        if self.context.state() != AudioContextState::Closed {
+            self.context.close().or_report();
+        }
+    }
+}
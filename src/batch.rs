@@ -0,0 +1,179 @@
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+};
+use web_sys::Element;
+use crate::{
+    own::ScopeValue,
+    schedule::animation_frame,
+};
+
+type Op = Box<dyn FnOnce()>;
+type WriteOp = Box<dyn FnOnce(&Element)>;
+
+/// The queued writes for one element.
+type Writes = (Element, Vec<WriteOp>);
+
+#[derive(Default)]
+struct BatchState {
+    depth: usize,
+    /// Queued writes grouped by element.  Writes to different elements don't affect
+    /// each other so only the order within each element matters.
+    writes: Vec<Writes>,
+    /// The index in `writes` of each element's writes, by element key
+    write_index: HashMap<usize, usize>,
+    reads: Vec<Op>,
+    frame: Option<ScopeValue>,
+}
+
+thread_local!{
+    static BATCH: RefCell<BatchState> = RefCell::new(BatchState::default());
+}
+
+/// Perform a DOM write on behalf of an `El` method - if a batch is in progress the
+/// write is queued, otherwise it's done immediately.  `key` identifies the `El`
+/// (see `flush_el`).
+pub(crate) fn write(key: usize, el: Element, f: impl FnOnce(&Element) + 'static) {
+    if BATCH.with(|b| b.borrow().depth) == 0 {
+        f(&el);
+        return;
+    }
+    BATCH.with(|b| {
+        let b = &mut *b.borrow_mut();
+        match b.write_index.get(&key) {
+            Some(i) => b.writes[*i].1.push(Box::new(f)),
+            None => {
+                b.write_index.insert(key, b.writes.len());
+                b.writes.push((el, vec![Box::new(f)]));
+            },
+        }
+    });
+}
+
+/// Take the queued writes for the `El` with `key` out of the queue, in order.
+fn take_el(key: usize) -> Option<Writes> {
+    return BATCH.with(|b| {
+        let b = &mut *b.borrow_mut();
+        let i = b.write_index.remove(&key)?;
+        let (el, writes) = &mut b.writes[i];
+        return Some((el.clone(), std::mem::take(writes)));
+    });
+}
+
+/// Apply any queued writes for the `El` with `key` now.  Called before changes that
+/// can't be queued (like adding and removing children) so they happen in the order
+/// they were made.
+pub(crate) fn flush_el(key: usize) {
+    let Some((el, writes)) = take_el(key) else {
+        return;
+    };
+    for w in writes {
+        w(&el);
+    }
+}
+
+/// Drop any queued writes for the `El` with `key` without applying them, for when
+/// the node is going to be reused for something else.
+pub(crate) fn discard_el(key: usize) {
+    drop(take_el(key));
+}
+
+fn flush_writes() {
+    loop {
+        let writes = BATCH.with(|b| {
+            let mut b = b.borrow_mut();
+            b.write_index.clear();
+            return std::mem::take(&mut b.writes);
+        });
+        if writes.is_empty() {
+            break;
+        }
+        for (el, writes) in writes {
+            for w in writes {
+                w(&el);
+            }
+        }
+    }
+}
+
+/// Decrements the batch depth when dropped, so a panic doesn't leave writes
+/// deferred forever.
+struct DepthGuard;
+
+impl DepthGuard {
+    fn new() -> DepthGuard {
+        BATCH.with(|b| b.borrow_mut().depth += 1);
+        return DepthGuard;
+    }
+}
+
+impl Drop for DepthGuard {
+    fn drop(&mut self) {
+        BATCH.with(|b| b.borrow_mut().depth -= 1);
+    }
+}
+
+fn flush_frame() {
+    BATCH.with(|b| b.borrow_mut().frame = None);
+    let depth = DepthGuard::new();
+    loop {
+        let reads = BATCH.with(|b| std::mem::take(&mut b.borrow_mut().reads));
+        if reads.is_empty() {
+            break;
+        }
+        for r in reads {
+            r();
+        }
+    }
+    drop(depth);
+    flush_writes();
+}
+
+fn schedule_frame(b: &mut BatchState) {
+    if b.frame.is_none() {
+        b.frame = Some(animation_frame(|_| flush_frame()));
+    }
+}
+
+/// Queue attribute, class, and text changes made through `El` methods within `f`
+/// and apply them together when `f` returns (at the end of the outermost `batch`
+/// if nested).  Structural changes (adding and removing children) are not
+/// deferred, but apply the element's queued writes first so changes keep their
+/// order.
+///
+/// If a `batch_frame` flush is pending, queued writes are applied with it instead.
+pub fn batch<T>(f: impl FnOnce() -> T) -> T {
+    let depth = DepthGuard::new();
+    let out = f();
+    drop(depth);
+    let flush = BATCH.with(|b| {
+        let b = b.borrow();
+        return b.depth == 0 && b.frame.is_none();
+    });
+    if flush {
+        flush_writes();
+    }
+    return out;
+}
+
+/// Like `batch`, but the queued writes are applied in the next animation frame,
+/// after any reads scheduled with `batch_read`.
+pub fn batch_frame<T>(f: impl FnOnce() -> T) -> T {
+    BATCH.with(|b| schedule_frame(&mut b.borrow_mut()));
+    let depth = DepthGuard::new();
+    let out = f();
+    drop(depth);
+    return out;
+}
+
+/// Schedule `f` to run in the next animation frame before any writes queued with
+/// `batch_frame` are applied.  Use this for layout reads (like measuring elements)
+/// to avoid interleaving reads and writes, which forces repeated layout.  Writes
+/// made in `f` are queued until all reads are done.
+pub fn batch_read(f: impl FnOnce() + 'static) {
+    BATCH.with(|b| {
+        let mut b = b.borrow_mut();
+        b.reads.push(Box::new(f));
+        schedule_frame(&mut b);
+    });
+}
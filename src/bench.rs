@@ -0,0 +1,143 @@
+//! Measurement utilities for tracking performance of the crate and of apps built on
+//! it, with the `bench` feature.  Run these in a browser, ex: from a
+//! `wasm-bindgen-test` test, and record the JSON from `results_json`.
+//!
+//! ```ignore
+//! #[wasm_bindgen_test]
+//! fn bench() {
+//!     console_log!("{}", rooting::bench::results_json(&rooting::bench::run_all()));
+//! }
+//! ```
+use gloo_utils::window;
+use js_sys::JSON;
+use wasm_bindgen::JsValue;
+use crate::{
+    el,
+    El,
+};
+
+/// Timing of repeated runs of a benchmark, in milliseconds.
+#[derive(Clone, Debug)]
+pub struct BenchResult {
+    pub name: String,
+    pub iterations: usize,
+    pub total_ms: f64,
+    pub mean_ms: f64,
+    pub min_ms: f64,
+    pub max_ms: f64,
+}
+
+impl BenchResult {
+    pub fn to_json(&self) -> String {
+        let name = JSON::stringify(&JsValue::from_str(&self.name)).map(String::from).unwrap_or_default();
+        return format!(
+            "{{\"name\":{},\"iterations\":{},\"total_ms\":{},\"mean_ms\":{},\"min_ms\":{},\"max_ms\":{}}}",
+            name,
+            self.iterations,
+            self.total_ms,
+            self.mean_ms,
+            self.min_ms,
+            self.max_ms
+        );
+    }
+}
+
+/// Format results as a JSON array.
+pub fn results_json(results: &[BenchResult]) -> String {
+    return format!("[{}]", results.iter().map(|r| r.to_json()).collect::<Vec<_>>().join(","));
+}
+
+fn now() -> f64 {
+    return window().performance().map(|p| p.now()).unwrap_or_else(js_sys::Date::now);
+}
+
+/// Time `iterations` runs of `f`.  `setup` is called before each run to produce
+/// its input, and isn't timed.
+pub fn bench_with_setup<
+    T,
+>(name: &str, iterations: usize, mut setup: impl FnMut() -> T, mut f: impl FnMut(T)) -> BenchResult {
+    let mut total = 0.;
+    let mut min = f64::INFINITY;
+    let mut max: f64 = 0.;
+    for _ in 0 .. iterations {
+        let input = setup();
+        let start = now();
+        f(input);
+        let elapsed = now() - start;
+        total += elapsed;
+        min = min.min(elapsed);
+        max = max.max(elapsed);
+    }
+    return BenchResult {
+        name: name.to_string(),
+        iterations,
+        total_ms: total,
+        mean_ms: total / iterations.max(1) as f64,
+        min_ms: if iterations == 0 {
+            0.
+        } else {
+            min
+        },
+        max_ms: max,
+    };
+}
+
+/// Time `iterations` runs of `f`.
+pub fn bench(name: &str, iterations: usize, mut f: impl FnMut()) -> BenchResult {
+    return bench_with_setup(name, iterations, || (), |_| f());
+}
+
+fn build_tree(depth: usize, breadth: usize) -> El {
+    let out = el("div");
+    if depth > 0 {
+        out.ref_extend((0 .. breadth).map(|_| build_tree(depth - 1, breadth)).collect());
+    }
+    return out;
+}
+
+/// Time building and dropping a tree of `div`s `depth` levels deep with `breadth`
+/// children per element.
+pub fn bench_tree_construction(depth: usize, breadth: usize, iterations: usize) -> BenchResult {
+    return bench(&format!("tree construction {}x{}", depth, breadth), iterations, || {
+        drop(build_tree(depth, breadth));
+    });
+}
+
+/// Time inserting `count` children one at a time at the middle of an element, then
+/// removing them one at a time from the front.
+pub fn bench_splice(count: usize, iterations: usize) -> BenchResult {
+    return bench_with_setup(
+        &format!("splice {}", count),
+        iterations,
+        || (el("div"), (0 .. count).map(|_| el("div")).collect::<Vec<_>>()),
+        |(parent, children)| {
+            for (i, child) in children.into_iter().enumerate() {
+                parent.ref_splice(i / 2, 0, vec![child]);
+            }
+            for _ in 0 .. count {
+                parent.ref_splice(0, 1, vec![]);
+            }
+        },
+    );
+}
+
+/// Time attaching `count` listeners to an element and detaching them by dropping
+/// the element.
+pub fn bench_listeners(count: usize, iterations: usize) -> BenchResult {
+    return bench(&format!("listeners {}", count), iterations, || {
+        let e = el("div");
+        for _ in 0 .. count {
+            e.ref_on("click", |_| { });
+        }
+        drop(e);
+    });
+}
+
+/// Run the crate benchmarks with default sizes.
+pub fn run_all() -> Vec<BenchResult> {
+    return vec![
+        bench_tree_construction(4, 8, 20),
+        bench_splice(1000, 20),
+        bench_listeners(1000, 20)
+    ];
+}
@@ -0,0 +1,74 @@
+use std::{
+    any::Any,
+    panic::{
+        catch_unwind,
+        AssertUnwindSafe,
+    },
+};
+use crate::El;
+#[cfg(feature = "futures")]
+use {
+    futures::Future,
+    crate::{
+        own::ScopeValue,
+        spawn::spawn_rooted,
+    },
+};
+
+/// Why an `error_boundary` builder failed.
+#[derive(Debug)]
+pub enum BoundaryError<E> {
+    /// The builder returned an error.
+    Err(E),
+    /// The builder panicked. This contains the panic message, if it was a string.
+    Panic(String),
+}
+
+fn panic_message(p: Box<dyn Any + Send>) -> String {
+    if let Some(s) = p.downcast_ref::<&str>() {
+        return s.to_string();
+    }
+    if let Some(s) = p.downcast_ref::<String>() {
+        return s.clone();
+    }
+    return "Unknown panic".to_string();
+}
+
+/// Build an element, producing a fallback element instead if building fails, so
+/// one widget's failure doesn't take down the rest of the page.
+///
+/// Panics in the builder are also caught, but only when panics unwind - with the
+/// default `panic = "abort"` on `wasm32` targets only errors returned from the
+/// builder can be contained.
+pub fn error_boundary<
+    E,
+>(builder: impl FnOnce() -> Result<El, E>, fallback: impl FnOnce(BoundaryError<E>) -> El) -> El {
+    match catch_unwind(AssertUnwindSafe(builder)) {
+        Ok(Ok(e)) => return e,
+        Ok(Err(e)) => return fallback(BoundaryError::Err(e)),
+        Err(p) => return fallback(BoundaryError::Panic(panic_message(p))),
+    }
+}
+
+/// Run a background task on behalf of `target` (see `spawn_rooted`). If the task
+/// fails, `target` is replaced in its parent with the fallback element (see
+/// `El::ref_replace`).  Attach the returned value to `target` with `.own()`.
+#[cfg(feature = "futures")]
+pub fn spawn_rooted_boundary<
+    E: 'static,
+>(
+    target: &El,
+    f: impl Future<Output = Result<(), E>> + 'static,
+    fallback: impl FnOnce(E) -> El + 'static,
+) -> ScopeValue {
+    let target = target.weak();
+    return spawn_rooted(async move {
+        let Err(e) = f.await else {
+            return;
+        };
+        let Some(target) = target.upgrade() else {
+            return;
+        };
+        target.ref_replace(vec![fallback(e)]);
+    });
+}
@@ -0,0 +1,56 @@
+use wasm_bindgen::JsCast;
+use web_sys::Element;
+use crate::{
+    el,
+    el_from_raw,
+    error::ReportExt,
+    El,
+};
+
+/// Creates many identical elements quickly.  The tag, classes, attributes and text
+/// are applied once to a prototype element, and each `build` clones it, which is
+/// much faster than creating and configuring each element separately.
+///
+/// ```ignore
+/// let cell = ElBuilder::new("td").classes(&["cell"]).attr("role", "gridcell");
+/// let cells = cell.build_n(10_000);
+/// ```
+pub struct ElBuilder {
+    proto: Element,
+}
+
+impl ElBuilder {
+    /// Start a builder for elements with the tag.  Invalid tags are handled like
+    /// `el`.
+    pub fn new(tag: &str) -> Self {
+        return ElBuilder { proto: el(tag).raw() };
+    }
+
+    pub fn classes(self, keys: &[&str]) -> Self {
+        let list = self.proto.class_list();
+        for k in keys {
+            list.add_1(k).or_report();
+        }
+        return self;
+    }
+
+    pub fn attr(self, key: &str, value: &str) -> Self {
+        self.proto.set_attribute(key, value).or_report();
+        return self;
+    }
+
+    pub fn text(self, text: &str) -> Self {
+        self.proto.set_text_content(Some(text));
+        return self;
+    }
+
+    /// Create an element.
+    pub fn build(&self) -> El {
+        return el_from_raw(self.proto.clone_node_with_deep(true).unwrap().unchecked_into::<Element>());
+    }
+
+    /// Create `n` elements.
+    pub fn build_n(&self, n: usize) -> Vec<El> {
+        return (0 .. n).map(|_| self.build()).collect();
+    }
+}
@@ -0,0 +1,127 @@
+use gloo_events::EventListener;
+use gloo_utils::window;
+use js_sys::Array;
+use wasm_bindgen::{
+    JsCast,
+    JsValue,
+};
+use web_sys::{
+    MessageChannel,
+    MessageEvent,
+    MessagePort,
+    Window,
+};
+use crate::own::{
+    scope_any,
+    ScopeValue,
+};
+#[cfg(feature = "serde")]
+use serde::{
+    de::DeserializeOwned,
+    Serialize,
+};
+
+/// One end of a `MessageChannel` (or any `MessagePort`), for communicating with
+/// other frames or workers.  Listeners added with `on_message` are removed and the
+/// port closed when this is dropped.
+pub struct RootedChannel {
+    port: MessagePort,
+    listeners: Vec<EventListener>,
+}
+
+impl RootedChannel {
+    /// Create a new channel, returning one end and the other end's port.  Transfer
+    /// the port to the other party, for instance with `post_window_message_port`.
+    pub fn new() -> Result<(RootedChannel, MessagePort), JsValue> {
+        let channel = MessageChannel::new()?;
+        return Ok((RootedChannel::from_port(channel.port1()), channel.port2()));
+    }
+
+    /// Wrap a port received from elsewhere.
+    pub fn from_port(port: MessagePort) -> RootedChannel {
+        return RootedChannel {
+            port,
+            listeners: vec![],
+        };
+    }
+
+    pub fn port(&self) -> &MessagePort {
+        return &self.port;
+    }
+
+    pub fn send_raw(&self, message: &JsValue) -> Result<(), JsValue> {
+        return self.port.post_message(message);
+    }
+
+    /// Call `cb` with the data of each message received.
+    pub fn on_message_raw(&mut self, mut cb: impl FnMut(JsValue) + 'static) {
+        self.listeners.push(EventListener::new(&self.port, "message", move |ev| {
+            cb(ev.dyn_ref::<MessageEvent>().unwrap().data());
+        }));
+        self.port.start();
+    }
+
+    /// Serialize and send a message.
+    #[cfg(feature = "serde")]
+    pub fn send<T: Serialize>(&self, message: &T) -> Result<(), JsValue> {
+        return self.send_raw(&serde_wasm_bindgen::to_value(message)?);
+    }
+
+    /// Call `cb` with each message received, deserialized.
+    #[cfg(feature = "serde")]
+    pub fn on_message<T: DeserializeOwned>(&mut self, mut cb: impl FnMut(Result<T, JsValue>) + 'static) {
+        self.on_message_raw(move |data| {
+            cb(serde_wasm_bindgen::from_value(data).map_err(JsValue::from));
+        });
+    }
+}
+
+impl Drop for RootedChannel {
+    fn drop(&mut self) {
+        self.listeners.clear();
+        self.port.close();
+    }
+}
+
+/// Send a message to another window (ex: an iframe's `content_window()` or
+/// `window.parent`) with `postMessage`.
+pub fn post_window_message(target: &Window, message: &JsValue, target_origin: &str) -> Result<(), JsValue> {
+    return target.post_message(message, target_origin);
+}
+
+/// Send a message to another window, transferring a `MessagePort` (ex: from
+/// `RootedChannel::new`) to set up a dedicated channel.
+pub fn post_window_message_port(
+    target: &Window,
+    message: &JsValue,
+    target_origin: &str,
+    port: MessagePort,
+) -> Result<(), JsValue> {
+    return target.post_message_with_transfer(message, target_origin, &Array::of1(&port));
+}
+
+/// Listen for messages sent to this window.  If `origin` is specified, messages
+/// from other origins are ignored. The listener is removed when the returned value
+/// is dropped.
+pub fn on_window_message(origin: Option<&str>, mut cb: impl FnMut(&MessageEvent) + 'static) -> ScopeValue {
+    let origin = origin.map(|o| o.to_string());
+    return scope_any(EventListener::new(&window(), "message", move |ev| {
+        let ev = ev.dyn_ref::<MessageEvent>().unwrap();
+        if let Some(origin) = &origin {
+            if ev.origin() != *origin {
+                return;
+            }
+        }
+        cb(ev);
+    }));
+}
+
+/// Like `on_window_message` but deserializes the message data.
+#[cfg(feature = "serde")]
+pub fn on_window_message_serde<
+    T: DeserializeOwned,
+>(origin: Option<&str>, mut cb: impl FnMut(Result<T, JsValue>, &MessageEvent) + 'static) -> ScopeValue {
+    return on_window_message(origin, move |ev| {
+        cb(serde_wasm_bindgen::from_value(ev.data()).map_err(JsValue::from), ev);
+    });
+}
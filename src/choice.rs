@@ -0,0 +1,209 @@
+use std::{
+    cell::Cell,
+    marker::PhantomData,
+    str::FromStr,
+};
+use gloo_events::EventListener;
+use wasm_bindgen::JsCast;
+use web_sys::{
+    HtmlInputElement,
+    HtmlSelectElement,
+};
+use crate::{
+    el,
+    ContainerEntry,
+    El,
+};
+
+thread_local!{
+    static NEXT_GROUP_ID: Cell<usize> = const {
+        Cell::new(0)
+    };
+}
+
+fn group_name() -> String {
+    let id = NEXT_GROUP_ID.with(|i| {
+        let id = i.get();
+        i.set(id + 1);
+        return id;
+    });
+    return format!("rooting-group-{}", id);
+}
+
+/// A `<select>` whose options are values of `T`, converted to and from option
+/// values with `ToString` and `FromStr`.  Change listeners are owned by the group
+/// and removed when it's dropped.
+pub struct SelectGroup<T: ToString + FromStr> {
+    el: El,
+    listeners: Vec<EventListener>,
+    _t: PhantomData<T>,
+}
+
+impl<T: ToString + FromStr + 'static> SelectGroup<T> {
+    /// Create the `<select>` with an option for each value and label.
+    pub fn new(options: impl IntoIterator<Item = (T, String)>) -> Self {
+        return SelectGroup {
+            el: el("select").extend(
+                options.into_iter().map(|(v, label)| el("option").attr("value", &v.to_string()).text(&label)).collect(),
+            ),
+            listeners: vec![],
+            _t: PhantomData,
+        };
+    }
+
+    fn raw(&self) -> HtmlSelectElement {
+        return self.el.raw().unchecked_into();
+    }
+
+    /// The selected value, or `None` if nothing is selected or it can't be parsed.
+    pub fn value(&self) -> Option<T> {
+        return T::from_str(&self.raw().value()).ok();
+    }
+
+    pub fn set_value(&self, value: &T) {
+        self.raw().set_value(&value.to_string());
+    }
+
+    /// Call `cb` with the new value when the user changes the selection.
+    pub fn on_change(&mut self, mut cb: impl FnMut(Option<T>) + 'static) -> &mut Self {
+        let raw = self.raw();
+        self.listeners.push(EventListener::new(&raw.clone(), "change", move |_| {
+            cb(T::from_str(&raw.value()).ok());
+        }));
+        return self;
+    }
+}
+
+impl<T: ToString + FromStr> ContainerEntry for SelectGroup<T> {
+    fn el(&self) -> &El {
+        return &self.el;
+    }
+}
+
+fn choice_inputs<T: FromStr>(container: &El) -> Vec<(HtmlInputElement, Option<T>)> {
+    let inputs = container.raw().query_selector_all("input").unwrap();
+    return (0 .. inputs.length()).filter_map(|i| inputs.get(i)?.dyn_into::<HtmlInputElement>().ok()).map(|input| {
+        let value = T::from_str(&input.value()).ok();
+        return (input, value);
+    }).collect();
+}
+
+fn choice_el(type_: &str, name: &str, value: String, label: &str) -> El {
+    return el(
+        "label",
+    ).extend(vec![el("input").attr("type", type_).attr("name", name).attr("value", &value), el("span").text(label)]);
+}
+
+/// A set of radio buttons whose values are `T`, converted to and from input values
+/// with `ToString` and `FromStr`.  Each option is an `<input>` in a `<label>` inside
+/// a container `<div>` with the `radiogroup` role. Change listeners are owned by the
+/// group and removed when it's dropped.
+pub struct RadioGroup<T: ToString + FromStr> {
+    el: El,
+    listeners: Vec<EventListener>,
+    _t: PhantomData<T>,
+}
+
+impl<T: ToString + FromStr + 'static> RadioGroup<T> {
+    pub fn new(options: impl IntoIterator<Item = (T, String)>) -> Self {
+        let name = group_name();
+        return RadioGroup {
+            el: el("div")
+                .attr("role", "radiogroup")
+                .extend(
+                    options.into_iter().map(|(v, label)| choice_el("radio", &name, v.to_string(), &label)).collect(),
+                ),
+            listeners: vec![],
+            _t: PhantomData,
+        };
+    }
+
+    /// The checked value, or `None` if nothing is checked or it can't be parsed.
+    pub fn value(&self) -> Option<T> {
+        return choice_inputs::<T>(&self.el).into_iter().find(|(i, _)| i.checked()).and_then(|(_, v)| v);
+    }
+
+    pub fn set_value(&self, value: &T) {
+        let value = value.to_string();
+        for (input, _) in choice_inputs::<T>(&self.el) {
+            input.set_checked(input.value() == value);
+        }
+    }
+
+    /// Call `cb` with the new value when the user changes the selection.
+    pub fn on_change(&mut self, mut cb: impl FnMut(Option<T>) + 'static) -> &mut Self {
+        let container = self.el.weak();
+        self.listeners.push(EventListener::new(&self.el.raw(), "change", move |_| {
+            let Some(container) = container.upgrade() else {
+                return;
+            };
+            cb(choice_inputs::<T>(&container).into_iter().find(|(i, _)| i.checked()).and_then(|(_, v)| v));
+        }));
+        return self;
+    }
+}
+
+impl<T: ToString + FromStr> ContainerEntry for RadioGroup<T> {
+    fn el(&self) -> &El {
+        return &self.el;
+    }
+}
+
+/// A set of checkboxes whose values are `T`, like `RadioGroup` but allowing
+/// multiple values to be checked.
+pub struct CheckboxGroup<T: ToString + FromStr> {
+    el: El,
+    listeners: Vec<EventListener>,
+    _t: PhantomData<T>,
+}
+
+impl<T: ToString + FromStr + 'static> CheckboxGroup<T> {
+    pub fn new(options: impl IntoIterator<Item = (T, String)>) -> Self {
+        let name = group_name();
+        return CheckboxGroup {
+            el: el("div")
+                .attr("role", "group")
+                .extend(
+                    options.into_iter().map(|(v, label)| choice_el("checkbox", &name, v.to_string(), &label)).collect(),
+                ),
+            listeners: vec![],
+            _t: PhantomData,
+        };
+    }
+
+    /// The checked values, skipping any that can't be parsed.
+    pub fn values(&self) -> Vec<T> {
+        return choice_inputs::<T>(&self.el).into_iter().filter(|(i, _)| i.checked()).filter_map(|(_, v)| v).collect();
+    }
+
+    pub fn set_values(&self, values: &[T]) {
+        let values = values.iter().map(|v| v.to_string()).collect::<Vec<_>>();
+        for (input, _) in choice_inputs::<T>(&self.el) {
+            input.set_checked(values.contains(&input.value()));
+        }
+    }
+
+    /// Call `cb` with the checked values when the user changes them.
+    pub fn on_change(&mut self, mut cb: impl FnMut(Vec<T>) + 'static) -> &mut Self {
+        let container = self.el.weak();
+        self.listeners.push(EventListener::new(&self.el.raw(), "change", move |_| {
+            let Some(container) = container.upgrade() else {
+                return;
+            };
+            cb(
+                choice_inputs::<T>(&container)
+                    .into_iter()
+                    .filter(|(i, _)| i.checked())
+                    .filter_map(|(_, v)| v)
+                    .collect(),
+            );
+        }));
+        return self;
+    }
+}
+
+impl<T: ToString + FromStr> ContainerEntry for CheckboxGroup<T> {
+    fn el(&self) -> &El {
+        return &self.el;
+    }
+}
@@ -1,4 +1,18 @@
-use crate::El;
+use std::{
+    any::Any,
+    collections::HashMap,
+    hash::Hash,
+    ops::{
+        Index,
+        IndexMut,
+    },
+};
+use crate::{
+    instrument::measure,
+    El,
+    Listeners,
+    ScopeValue,
+};
 
 /// A trait describing data structures that have a representative `El`.  This is
 /// for use with `Container`.
@@ -16,40 +30,114 @@ pub trait ContainerEntry {
 pub struct Container<T: ContainerEntry> {
     entries: Vec<T>,
     el: El,
+    changes: Listeners<ContainerChange>,
+    /// The models from the last `sync_from`, if the entries haven't been changed
+    /// otherwise since.
+    synced: Option<Box<dyn Any>>,
+}
+
+/// A change to the entries of a `Container`, see `Container::on_change`.  Indexes
+/// refer to the entries at the time of the change.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ContainerChange {
+    /// `count` entries were added at the end, starting at `index` (`push`,
+    /// `extend`).
+    Push {
+        index: usize,
+        count: usize,
+    },
+    /// An entry was inserted at `index`.
+    Insert {
+        index: usize,
+    },
+    /// The entry at `index` was removed (`remove`, `pop`).
+    Remove {
+        index: usize,
+    },
+    /// `remove` entries at `offset` were replaced with `add` entries.
+    Splice {
+        offset: usize,
+        remove: usize,
+        add: usize,
+    },
+    /// All entries were removed.
+    Clear,
+    /// The entry at `from` was moved to `to`.
+    Move {
+        from: usize,
+        to: usize,
+    },
+    /// Entries were rotated left by `n`.
+    Rotate {
+        n: usize,
+    },
 }
 
 impl<T: ContainerEntry> Container<T> {
     pub fn new(el: El) -> Container<T> {
         return Container {
             entries: vec![],
-            el: el,
+            el,
+            changes: Listeners::new(),
+            synced: None,
         };
     }
 
-    pub fn iter(&self) -> core::slice::Iter<T> {
+    fn changed(&mut self, change: ContainerChange) {
+        self.synced = None;
+        self.changes.emit(&change);
+    }
+
+    /// Call `cb` after each change to the entries, until the returned value is
+    /// dropped.  Changes to entries in place (ex: via `get_mut`) aren't reported.
+    pub fn on_change(&self, cb: impl FnMut(&ContainerChange) + 'static) -> ScopeValue {
+        return self.changes.listen(cb);
+    }
+
+    pub fn iter(&self) -> core::slice::Iter<'_, T> {
         return self.entries.iter();
     }
 
+    /// Iterate entries mutably, to update their state in place.  Don't change which
+    /// element an entry returns from `el()`, or the container will get out of sync.
+    pub fn iter_mut(&mut self) -> core::slice::IterMut<'_, T> {
+        return self.entries.iter_mut();
+    }
+
     pub fn clear(&mut self) {
         self.el.ref_clear();
         self.entries.clear();
+        self.changed(ContainerChange::Clear);
     }
 
     pub fn push(&mut self, entry: T) {
         self.el.ref_push(entry.el().clone());
         self.entries.push(entry);
+        self.changed(ContainerChange::Push {
+            index: self.entries.len() - 1,
+            count: 1,
+        });
     }
 
     pub fn extend(&mut self, entries: Vec<T>) {
+        let index = self.entries.len();
+        let count = entries.len();
         self.el.ref_extend(entries.iter().map(|e| e.el().clone()).collect());
         self.entries.extend(entries);
+        self.changed(ContainerChange::Push {
+            index,
+            count,
+        });
     }
 
     pub fn insert(&mut self, i: usize, entry: T) {
         self.el.ref_splice(i, 0, vec![entry.el().clone()]);
         self.entries.insert(i, entry);
+        self.changed(ContainerChange::Insert { index: i });
     }
 
+    /// Listeners are notified of the change before the removed entries are
+    /// returned.
     pub fn splice(
         &mut self,
         offset: usize,
@@ -57,6 +145,11 @@ impl<T: ContainerEntry> Container<T> {
         add: Vec<T>,
     ) -> std::vec::Splice<'_, std::vec::IntoIter<T>> {
         self.el.ref_splice(offset, remove, add.iter().map(|e| e.el().clone()).collect());
+        self.changed(ContainerChange::Splice {
+            offset,
+            remove,
+            add: add.len(),
+        });
         return self.entries.splice(offset .. offset + remove, add);
     }
 
@@ -80,7 +173,9 @@ impl<T: ContainerEntry> Container<T> {
         let len = self.entries.len();
         if len > 0 {
             self.el.ref_splice(len - 1, 1, vec![]);
-            return self.entries.pop();
+            let out = self.entries.pop();
+            self.changed(ContainerChange::Remove { index: len - 1 });
+            return out;
         } else {
             return None;
         }
@@ -102,23 +197,316 @@ impl<T: ContainerEntry> Container<T> {
         return self.entries.get(i);
     }
 
+    /// See the warning on `iter_mut`.
+    pub fn get_mut(&mut self, i: usize) -> Option<&mut T> {
+        return self.entries.get_mut(i);
+    }
+
     pub fn remove(&mut self, i: usize) -> T {
         self.el.ref_splice(i, 1, vec![]);
-        return self.entries.remove(i);
+        let out = self.entries.remove(i);
+        self.changed(ContainerChange::Remove { index: i });
+        return out;
+    }
+
+    /// Move the entry at `from` so it ends up at index `to`.  The entry's element is
+    /// moved in the DOM directly, without being dropped.
+    pub fn move_entry(&mut self, from: usize, to: usize) {
+        if from >= self.entries.len() || to >= self.entries.len() {
+            panic!("Move index out of bounds: from {} to {} with length {}", from, to, self.entries.len());
+        }
+        self.el.ref_move_child(from, to);
+        let entry = self.entries.remove(from);
+        self.entries.insert(to, entry);
+        self.changed(ContainerChange::Move {
+            from,
+            to,
+        });
+    }
+
+    /// Rotate entries left by `n`, like `slice::rotate_left`: the entry at `n` becomes
+    /// the first.  This moves the fewest elements necessary in the DOM.
+    pub fn rotate(&mut self, n: usize) {
+        let len = self.entries.len();
+        if len == 0 {
+            return;
+        }
+        let n = n % len;
+        measure("Container rotate", &self.el, || {
+            if n <= len / 2 {
+                for _ in 0 .. n {
+                    self.el.ref_move_child(0, len - 1);
+                }
+            } else {
+                for _ in 0 .. len - n {
+                    self.el.ref_move_child(len - 1, 0);
+                }
+            }
+        });
+        self.entries.rotate_left(n);
+        self.changed(ContainerChange::Rotate { n });
+    }
+
+    /// Make the entries match `models`, with as few DOM changes as possible.
+    ///
+    /// Entries are matched to the models from the previous `sync_from` by equality;
+    /// matched entries are kept (moving the fewest necessary, based on the longest
+    /// increasing subsequence).  Remaining entries are reused in order for the
+    /// remaining models by calling `update`, and then any extra entries are removed
+    /// or new entries created with `build`.
+    ///
+    /// If the container was modified by other methods since the last `sync_from`
+    /// (or this is the first) there are no previous models, so all entries are
+    /// reused with `update`.
+    pub fn sync_from<
+        M: PartialEq + Clone + 'static,
+    >(&mut self, models: &[M], build: impl Fn(&M) -> T, update: impl Fn(&mut T, &M)) {
+        let el = self.el.clone();
+        measure("Container sync_from", &el, || self.sync_from_(models, build, update));
+    }
+
+    fn sync_from_<
+        M: PartialEq + Clone + 'static,
+    >(&mut self, models: &[M], build: impl Fn(&M) -> T, update: impl Fn(&mut T, &M)) {
+        let old_models =
+            self
+                .synced
+                .take()
+                .and_then(|s| s.downcast::<Vec<M>>().ok())
+                .filter(|s| s.len() == self.entries.len())
+                .map(|s| *s)
+                .unwrap_or_default();
+
+        // Match new models to old entries, first by equality then by position
+        let mut old_used = vec![false; self.entries.len()];
+        let mut matched: Vec<Option<(usize, bool)>> = models.iter().map(|m| {
+            let i = old_models.iter().enumerate().position(|(i, o)| !old_used[i] && o == m)?;
+            old_used[i] = true;
+            return Some((i, false));
+        }).collect();
+        let mut unused_old = (0 .. self.entries.len()).filter(|i| !old_used[*i]).collect::<Vec<_>>().into_iter();
+        for m in &mut matched {
+            if m.is_some() {
+                continue;
+            }
+            let Some(i) = unused_old.next() else {
+                break;
+            };
+            old_used[i] = true;
+            *m = Some((i, true));
+        }
+
+        // Remove unmatched entries
+        for i in (0 .. self.entries.len()).rev() {
+            if !old_used[i] {
+                self.remove(i);
+            }
+        }
+
+        // Identify kept entries by element, since indexes change as entries move
+        let kept_els = self.entries.iter().map(|e| e.el().clone()).collect::<Vec<_>>();
+        let kept_index = {
+            let mut kept_index = vec![0; old_used.len()];
+            let mut at = 0;
+            for (i, used) in old_used.iter().enumerate() {
+                if *used {
+                    kept_index[i] = at;
+                    at += 1;
+                }
+            }
+            kept_index
+        };
+        let sequence = matched.iter().filter_map(|m| m.map(|(i, _)| kept_index[i])).collect::<Vec<_>>();
+        let stay = longest_increasing_subsequence(&sequence);
+
+        // Move and insert from the end, placing each entry before the one after it
+        let mut seq_i = sequence.len();
+        let mut next_el: Option<El> = None;
+        for (j, m) in matched.iter().enumerate().rev() {
+            let anchor = match &next_el {
+                Some(next) => self.el.ref_index_of(next).unwrap(),
+                None => self.entries.len(),
+            };
+            match m {
+                Some((_, reuse)) => {
+                    seq_i -= 1;
+                    let el = kept_els[sequence[seq_i]].clone();
+                    let from = self.el.ref_index_of(&el).unwrap();
+                    if !stay[seq_i] {
+                        let to = if from < anchor {
+                            anchor - 1
+                        } else {
+                            anchor
+                        };
+                        self.move_entry(from, to);
+                    }
+                    if *reuse {
+                        let at = self.el.ref_index_of(&el).unwrap();
+                        update(&mut self.entries[at], &models[j]);
+                    }
+                    next_el = Some(el);
+                },
+                None => {
+                    let entry = build(&models[j]);
+                    next_el = Some(entry.el().clone());
+                    self.insert(anchor, entry);
+                },
+            }
+        }
+        self.synced = Some(Box::new(models.to_vec()));
+    }
+
+    /// Binary search entries sorted by `f`, like `slice::binary_search_by_key`.
+    pub fn binary_search_by_key<B: Ord>(&self, b: &B, f: impl FnMut(&T) -> B) -> Result<usize, usize> {
+        return self.entries.binary_search_by_key(b, f);
+    }
+
+    /// Insert the entry into entries sorted by `f`, after any entries with an equal
+    /// key.  Returns the index it was inserted at.
+    pub fn insert_sorted_by_key<B: Ord>(&mut self, entry: T, mut f: impl FnMut(&T) -> B) -> usize {
+        let key = f(&entry);
+        let i = self.entries.partition_point(|e| f(e) <= key);
+        self.insert(i, entry);
+        return i;
     }
 }
 
+/// Returns whether each element of `seq` is part of a longest strictly increasing
+/// subsequence.
+fn longest_increasing_subsequence(seq: &[usize]) -> Vec<bool> {
+    // Indexes into `seq` of the last element of the best subsequence of each length
+    let mut tails: Vec<usize> = vec![];
+    let mut prev = vec![None; seq.len()];
+    for (i, v) in seq.iter().enumerate() {
+        let len = tails.partition_point(|t| seq[*t] < *v);
+        if len > 0 {
+            prev[i] = Some(tails[len - 1]);
+        }
+        if len == tails.len() {
+            tails.push(i);
+        } else {
+            tails[len] = i;
+        }
+    }
+    let mut out = vec![false; seq.len()];
+    let mut at = tails.last().copied();
+    while let Some(i) = at {
+        out[i] = true;
+        at = prev[i];
+    }
+    return out;
+}
+
 impl<T: ContainerEntry> ContainerEntry for Container<T> {
     fn el(&self) -> &El {
         return &self.el;
     }
 }
 
+/// Like `Container` but entries are identified by key rather than position.  New
+/// keys are added at the end, and replacing the entry for an existing key keeps its
+/// position.
+pub struct ContainerMap<K: Eq + Hash + Clone, T: ContainerEntry> {
+    order: Vec<K>,
+    entries: HashMap<K, T>,
+    el: El,
+}
+
+impl<K: Eq + Hash + Clone, T: ContainerEntry> ContainerMap<K, T> {
+    pub fn new(el: El) -> ContainerMap<K, T> {
+        return ContainerMap {
+            order: vec![],
+            entries: HashMap::new(),
+            el,
+        };
+    }
+
+    /// Add or replace the entry for the key, returning the replaced entry.
+    pub fn insert(&mut self, key: K, entry: T) -> Option<T> {
+        if self.entries.contains_key(&key) {
+            let i = self.order.iter().position(|k| *k == key).unwrap();
+            self.el.ref_splice(i, 1, vec![entry.el().clone()]);
+        } else {
+            self.el.ref_push(entry.el().clone());
+            self.order.push(key.clone());
+        }
+        return self.entries.insert(key, entry);
+    }
+
+    pub fn remove(&mut self, key: &K) -> Option<T> {
+        let entry = self.entries.remove(key)?;
+        let i = self.order.iter().position(|k| k == key).unwrap();
+        self.order.remove(i);
+        self.el.ref_splice(i, 1, vec![]);
+        return Some(entry);
+    }
+
+    pub fn clear(&mut self) {
+        self.el.ref_clear();
+        self.order.clear();
+        self.entries.clear();
+    }
+
+    pub fn get(&self, key: &K) -> Option<&T> {
+        return self.entries.get(key);
+    }
+
+    pub fn get_mut(&mut self, key: &K) -> Option<&mut T> {
+        return self.entries.get_mut(key);
+    }
+
+    pub fn contains_key(&self, key: &K) -> bool {
+        return self.entries.contains_key(key);
+    }
+
+    pub fn len(&self) -> usize {
+        return self.order.len();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.order.is_empty();
+    }
+
+    /// Keys and entries in display order.
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &T)> {
+        return self.order.iter().map(|k| (k, &self.entries[k]));
+    }
+}
+
+impl<K: Eq + Hash + Clone, T: ContainerEntry> ContainerEntry for ContainerMap<K, T> {
+    fn el(&self) -> &El {
+        return &self.el;
+    }
+}
+
 impl<'a, T: ContainerEntry> IntoIterator for &'a Container<T> {
     type Item = &'a T;
     type IntoIter = std::slice::Iter<'a, T>;
 
     fn into_iter(self) -> Self::IntoIter {
-        return (&self.entries).into_iter();
+        return self.entries.iter();
+    }
+}
+
+impl<'a, T: ContainerEntry> IntoIterator for &'a mut Container<T> {
+    type Item = &'a mut T;
+    type IntoIter = std::slice::IterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        return self.entries.iter_mut();
+    }
+}
+
+impl<T: ContainerEntry> Index<usize> for Container<T> {
+    type Output = T;
+
+    fn index(&self, i: usize) -> &T {
+        return &self.entries[i];
+    }
+}
+
+impl<T: ContainerEntry> IndexMut<usize> for Container<T> {
+    fn index_mut(&mut self, i: usize) -> &mut T {
+        return &mut self.entries[i];
     }
 }
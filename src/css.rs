@@ -0,0 +1,144 @@
+use std::{
+    collections::hash_map::DefaultHasher,
+    hash::{
+        Hash,
+        Hasher,
+    },
+    rc::Rc,
+};
+use gloo_utils::document;
+use wasm_bindgen::JsValue;
+use web_sys::Element;
+use crate::{
+    error::ReportExt,
+    El,
+};
+
+struct StyleHandle_ {
+    el: Option<Element>,
+    prefix: Option<String>,
+}
+
+impl Drop for StyleHandle_ {
+    fn drop(&mut self) {
+        if let Some(el) = &self.el {
+            el.remove();
+        }
+    }
+}
+
+/// Styles injected into the document with `style_sheet` or `style_sheet_scoped`.
+/// The styles are removed when the last clone of the handle is dropped.
+#[derive(Clone)]
+pub struct StyleHandle(Rc<StyleHandle_>);
+
+impl StyleHandle {
+    /// The actual class name for a class used in the rules.  For unscoped sheets this
+    /// is `name` unchanged.
+    pub fn class(&self, name: &str) -> String {
+        match &self.0.prefix {
+            Some(prefix) => return format!("{}-{}", prefix, name),
+            None => return name.to_string(),
+        }
+    }
+}
+
+fn inject(rules: &str, prefix: Option<String>) -> StyleHandle {
+    let el = (|| {
+        let head = document().head().ok_or_else(|| JsValue::from_str("Document has no head"))?;
+        let el = document().create_element("style")?;
+        el.set_text_content(Some(rules));
+        head.append_child(&el)?;
+        return Ok(el);
+    })().or_report();
+    return StyleHandle(Rc::new(StyleHandle_ {
+        el,
+        prefix,
+    }));
+}
+
+/// Add the css rules to the document as-is.
+pub fn style_sheet(rules: &str) -> StyleHandle {
+    return inject(rules, None);
+}
+
+fn is_ident_start(c: char) -> bool {
+    return c.is_alphabetic() || c == '_' || c == '-';
+}
+
+fn is_ident(c: char) -> bool {
+    return is_ident_start(c) || c.is_ascii_digit();
+}
+
+/// Rewrite class selectors (`.name`) in the rules to `.prefix-name`, leaving
+/// declarations alone.  Blocks are assumed to contain declarations unless their
+/// prelude is a grouping at-rule like `@media`.
+fn scope_rules(rules: &str, prefix: &str) -> String {
+    let mut out = String::with_capacity(rules.len());
+    // For each open block, whether it contains rules (vs declarations)
+    let mut blocks: Vec<bool> = vec![];
+    let mut prelude = String::new();
+    let mut chars = rules.chars().peekable();
+    while let Some(c) = chars.next() {
+        let in_rules = blocks.last().copied().unwrap_or(true);
+        match c {
+            '{' => {
+                let p = prelude.trim_start();
+                blocks.push(
+                    in_rules &&
+                        ["@media", "@supports", "@container", "@layer", "@document"].iter().any(|a| p.starts_with(a)),
+                );
+                prelude.clear();
+                out.push(c);
+            },
+            '}' => {
+                blocks.pop();
+                prelude.clear();
+                out.push(c);
+            },
+            ';' => {
+                prelude.clear();
+                out.push(c);
+            },
+            '.' if in_rules && !prelude.trim_start().starts_with('@') &&
+                chars.peek().is_some_and(|c| is_ident_start(*c)) => {
+                out.push('.');
+                out.push_str(prefix);
+                out.push('-');
+                while let Some(c) = chars.next_if(|c| is_ident(*c)) {
+                    out.push(c);
+                    prelude.push(c);
+                }
+            },
+            c => {
+                prelude.push(c);
+                out.push(c);
+            },
+        }
+    }
+    return out;
+}
+
+/// Add the css rules to the document, prefixing class selectors with a prefix
+/// derived from the rules so they don't clash with other styles.  Use
+/// `StyleHandle::class` or `El::scoped_class` to get the prefixed names.
+pub fn style_sheet_scoped(rules: &str) -> StyleHandle {
+    let mut hasher = DefaultHasher::new();
+    rules.hash(&mut hasher);
+    let prefix = format!("r{:x}", hasher.finish() & 0xffff_ffff);
+    return inject(&scope_rules(rules, &prefix), Some(prefix));
+}
+
+impl El {
+    /// Add the class `name` from the style sheet, keeping the styles alive as long as
+    /// this element.
+    pub fn scoped_class(self, handle: &StyleHandle, name: &str) -> Self {
+        self.ref_scoped_class(handle, name);
+        return self;
+    }
+
+    pub fn ref_scoped_class(&self, handle: &StyleHandle, name: &str) -> &Self {
+        self.ref_classes(&[&handle.class(name)]);
+        return self.ref_own(|_| handle.clone());
+    }
+}
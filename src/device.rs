@@ -0,0 +1,51 @@
+use gloo_events::EventListener;
+use gloo_utils::window;
+use wasm_bindgen::JsCast;
+use web_sys::{
+    DeviceOrientationEvent,
+    Gamepad,
+};
+use crate::{
+    schedule::animation_loop,
+    scope_any,
+    ScopeValue,
+};
+
+/// Poll the connected gamepads every animation frame, calling `cb` with them, until
+/// the returned value is dropped.  Gamepad state isn't evented so polling is the
+/// only way to read it.
+pub fn on_gamepad_frame(mut cb: impl FnMut(&[Gamepad]) + 'static) -> ScopeValue {
+    return animation_loop(move |_| {
+        let Ok(pads) = window().navigator().get_gamepads() else {
+            return;
+        };
+        let pads = pads.iter().filter_map(|p| p.dyn_into::<Gamepad>().ok()).collect::<Vec<_>>();
+        cb(&pads);
+    });
+}
+
+/// Device orientation angles in degrees, `None` if the device doesn't report them.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Orientation {
+    /// Rotation around the z axis (compass direction if `absolute`), 0 to 360
+    pub alpha: Option<f64>,
+    /// Front to back tilt, -180 to 180
+    pub beta: Option<f64>,
+    /// Left to right tilt, -90 to 90
+    pub gamma: Option<f64>,
+    pub absolute: bool,
+}
+
+/// Call `cb` when the device orientation changes (`deviceorientation`), until the
+/// returned value is dropped.
+pub fn on_device_orientation(mut cb: impl FnMut(Orientation) + 'static) -> ScopeValue {
+    return scope_any(EventListener::new(&window(), "deviceorientation", move |ev| {
+        let ev = ev.unchecked_ref::<DeviceOrientationEvent>();
+        cb(Orientation {
+            alpha: ev.alpha(),
+            beta: ev.beta(),
+            gamma: ev.gamma(),
+            absolute: ev.absolute(),
+        });
+    }));
+}
@@ -0,0 +1,111 @@
+use std::{
+    cell::RefCell,
+    rc::Rc,
+};
+use gloo_events::EventListener;
+use gloo_utils::document;
+use wasm_bindgen::JsCast;
+use web_sys::{
+    HtmlDialogElement,
+    HtmlElement,
+    MouseEvent,
+};
+use crate::{
+    el,
+    error::ReportExt,
+    ContainerEntry,
+    El,
+};
+
+/// A `<dialog>` element with helpers for modal use.  When the dialog closes, focus
+/// returns to the element that was focused when it was opened.
+///
+/// Add it to the tree via `.el()` (it implements `ContainerEntry`).
+#[derive(Clone)]
+pub struct Dialog {
+    el: El,
+    opener: Rc<RefCell<Option<HtmlElement>>>,
+}
+
+/// Create a new `<dialog>` element.
+pub fn dialog_el() -> Dialog {
+    let opener = Rc::new(RefCell::new(None::<HtmlElement>));
+    let el = el("dialog").own(|e| {
+        let opener = opener.clone();
+        return EventListener::new(&e.raw(), "close", move |_| {
+            let Some(opener) = opener.borrow_mut().take() else {
+                return;
+            };
+            if opener.is_connected() {
+                _ = opener.focus();
+            }
+        });
+    });
+    return Dialog {
+        el,
+        opener,
+    };
+}
+
+impl Dialog {
+    pub fn raw(&self) -> HtmlDialogElement {
+        return self.el.raw().unchecked_into();
+    }
+
+    /// Show the dialog as a modal, remembering the currently focused element to
+    /// restore focus to on close.
+    pub fn show_modal(&self) -> &Self {
+        *self.opener.borrow_mut() = document().active_element().and_then(|e| e.dyn_into::<HtmlElement>().ok());
+        self.raw().show_modal().or_report();
+        return self;
+    }
+
+    /// Show the dialog without making it modal.
+    pub fn show(&self) -> &Self {
+        *self.opener.borrow_mut() = document().active_element().and_then(|e| e.dyn_into::<HtmlElement>().ok());
+        self.raw().show();
+        return self;
+    }
+
+    pub fn close(&self) -> &Self {
+        self.raw().close();
+        return self;
+    }
+
+    pub fn is_open(&self) -> bool {
+        return self.raw().open();
+    }
+
+    /// Call `cb` when the dialog closes (by `close`, the escape key, or a form with
+    /// `method="dialog"`).  The listener is removed when the dialog element is
+    /// dropped.
+    pub fn on_close(&self, mut cb: impl FnMut() + 'static) -> &Self {
+        self.el.ref_on("close", move |_| cb());
+        return self;
+    }
+
+    /// Close the dialog when the backdrop (the area outside the dialog box) is
+    /// clicked.
+    pub fn close_on_backdrop_click(&self) -> &Self {
+        let dialog = self.raw();
+        self.el.ref_on("click", move |ev| {
+            let ev = ev.dyn_ref::<MouseEvent>().unwrap();
+            if ev.target().as_ref() != Some(dialog.as_ref()) {
+                return;
+            }
+            let rect = dialog.get_bounding_client_rect();
+            let x = ev.client_x() as f64;
+            let y = ev.client_y() as f64;
+            if x < rect.left() || x > rect.right() || y < rect.top() || y > rect.bottom() {
+                dialog.close();
+            }
+        });
+        return self;
+    }
+}
+
+impl ContainerEntry for Dialog {
+    fn el(&self) -> &El {
+        return &self.el;
+    }
+}
@@ -0,0 +1,32 @@
+use std::cell::RefCell;
+use web_sys::Document;
+
+thread_local!{
+    static DOCUMENT: RefCell<Vec<Document>> = const {
+        RefCell::new(vec![])
+    };
+}
+
+struct DocumentGuard;
+
+impl Drop for DocumentGuard {
+    fn drop(&mut self) {
+        DOCUMENT.with(|d| d.borrow_mut().pop());
+    }
+}
+
+/// Run `f` with `doc` as the current document, so `el`, `template_el` and other
+/// functions that create elements create them in `doc`.  Use this to build
+/// elements for another window (ex: opened with `window.open`) or an iframe.
+/// Calls can be nested.
+pub fn with_document<T>(doc: &Document, f: impl FnOnce() -> T) -> T {
+    DOCUMENT.with(|d| d.borrow_mut().push(doc.clone()));
+    let _guard = DocumentGuard;
+    return f();
+}
+
+/// The document set by the innermost `with_document`, or the main window's
+/// document.
+pub fn current_document() -> Document {
+    return DOCUMENT.with(|d| d.borrow().last().cloned()).unwrap_or_else(gloo_utils::document);
+}
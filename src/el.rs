@@ -4,16 +4,27 @@ use std::{
         Rc,
     },
     cell::{
+        Ref,
         RefCell,
+        RefMut,
     },
+    collections::{
+        HashMap,
+        HashSet,
+    },
+    fmt,
 };
 use gloo_events::{
     EventListener,
     EventListenerOptions,
 };
-use gloo_utils::document;
-use wasm_bindgen::JsCast;
+use js_sys::Reflect;
+use wasm_bindgen::{
+    JsCast,
+    JsValue,
+};
 use web_sys::{
+    Document,
     Element,
     Node,
     Event,
@@ -21,14 +32,36 @@ use web_sys::{
     ResizeObserverSize,
 };
 use crate::{
+    batch,
+    document::{
+        current_document,
+        with_document,
+    },
+    error::{
+        report_error,
+        ReportExt,
+    },
     own::{
         scope_any,
         ScopeValue,
     },
+    pool::{
+        recycle,
+        WeakPool,
+    },
+    instrument::{
+        measure,
+        trace,
+    },
     resize::{
         ResizeObserver,
     },
 };
+#[cfg(feature = "futures")]
+use crate::spawn::{
+    own_stream,
+    ticker,
+};
 
 pub(crate) struct El_ {
     pub(crate) el: Element,
@@ -36,61 +69,280 @@ pub(crate) struct El_ {
     index_in_parent: usize,
     children: Vec<El>,
     local: Vec<ScopeValue>,
+    /// Set while the element is mounted in the document by a `Root`.
+    pub(crate) mounted: bool,
+    /// The text last set with `ref_text` and similar, if the children haven't been
+    /// modified since.
+    text_cache: Option<String>,
+    /// Attribute values last set with `ref_attr` and similar.
+    attr_cache: HashMap<String, String>,
+    /// A label for debugging, see `El::ref_debug_name`.
+    debug_name: Option<String>,
+    /// Called on copies made with `El::deep_clone`, see `El::ref_on_deep_clone`.
+    clone_hooks: Vec<CloneHook>,
+    /// The pool to return the node to when dropped, see `ElPool`.
+    pool: Option<WeakPool>,
+    /// Owned values that are replaced rather than accumulated, by key, see
+    /// `El::set_owned_slot`.
+    owned_slots: HashMap<&'static str, ScopeValue>,
+}
+
+type CloneHook = Rc<dyn Fn(&El)>;
+
+impl Drop for El_ {
+    fn drop(&mut self) {
+        #[cfg(feature = "log")]
+        if log::log_enabled!(log::Level::Trace) {
+            log::trace!(
+                "rooting drop {} ({} owned values, {} children)",
+                crate::instrument::describe(&self.el.tag_name(), None, self.debug_name.as_deref()),
+                self.local.len(),
+                self.children.len()
+            );
+        }
+        if let Some(pool) = self.pool.take() {
+            // Drop everything using the node before handing it back
+            self.local.clear();
+            self.owned_slots.clear();
+            self.children.clear();
+            batch::discard_el(self.batch_key());
+            recycle(&pool, &self.el);
+        } else {
+            // Apply now since the key can be reused by a new element
+            batch::flush_el(self.batch_key());
+        }
+    }
 }
 
 impl El_ {
-    fn splice(&mut self, self2: &Rc<RefCell<El_>>, offset: usize, remove: usize, add: Vec<El>) {
-        let el_children = self.el.children();
+    /// Identifies the element's queued writes while a batch is in progress, see
+    /// `batch::write`.
+    fn batch_key(&self) -> usize {
+        return self as *const El_ as usize;
+    }
+
+    /// Check that a splice range is within the scoped children.
+    fn check_splice_range(&self, offset: usize, remove: usize) -> Result<(), String> {
+        let len = self.children.len();
+        if offset.checked_add(remove).is_none_or(|end| end > len) {
+            return Err(
+                format!(
+                    "Splice range {}..{} is outside of the {} scoped children of <{}>",
+                    offset,
+                    offset.saturating_add(remove),
+                    len,
+                    self.el.tag_name().to_ascii_lowercase()
+                ),
+            );
+        }
+        return Ok(());
+    }
+
+    /// Check that the scoped children are all still attached to this element in the
+    /// DOM.  Other DOM children are fine, since children are positioned relative to
+    /// their scoped siblings.
+    fn check_dom_children(&self) -> Result<(), String> {
+        let parent: &Node = &self.el;
+        let detached = self.children.iter().filter(|c| c.inner().el.parent_node().as_ref() != Some(parent)).count();
+        if detached > 0 {
+            return Err(
+                format!(
+                    "{} of the {} scoped children of <{}> were moved or removed outside of rooting",
+                    detached,
+                    self.children.len(),
+                    self.el.tag_name().to_ascii_lowercase()
+                ),
+            );
+        }
+        return Ok(());
+    }
+
+    /// The node to insert new children before so they end up at scoped index
+    /// `index`: the first scoped child from `index` on that's still attached, or
+    /// `None` to append.
+    fn insert_ref(&self, index: usize) -> Option<Node> {
+        let parent: &Node = &self.el;
+        return self
+            .children
+            .get(index..)?
+            .iter()
+            .map(|c| Node::from(c.inner().el.clone()))
+            .find(|n| n.parent_node().as_ref() == Some(parent));
+    }
+
+    /// Returns the removed children, which should be dropped after the borrow of
+    /// this element is released so their drop code can access it.
+    #[must_use]
+    fn splice(&mut self, self2: &Rc<RefCell<El_>>, offset: usize, remove: usize, add: Vec<El>) -> Vec<El> {
+        batch::flush_el(self.batch_key());
+        self.text_cache = None;
 
-        // Remove existing dom children
-        for _ in 0 .. remove {
-            el_children.get_with_index(offset as u32).unwrap().remove();
+        // Remove existing dom children.  Children are located by their own nodes
+        // rather than DOM index so nodes added by other code don't shift positions.
+        for child in &self.children[offset .. offset + remove] {
+            child.inner().el.remove();
         }
 
         // Add new dom children + update parent state for new scope children
-        let insert_ref = el_children.get_with_index(offset as u32);
-        let insert_ref = insert_ref.as_ref().map(|x| x as &Node);
+        let insert_ref = self.insert_ref(offset + remove);
+        let insert_ref = insert_ref.as_ref();
         for (i, child) in add.iter().enumerate() {
-            let mut c = child.0.borrow_mut();
+            let mut c = child.inner_mut();
             c.parent = Some(Rc::downgrade(self2));
             c.index_in_parent = offset + i;
-            self.el.insert_before(&c.el, insert_ref).unwrap();
+            self.el.insert_before(&c.el, insert_ref).or_report();
         }
 
         // Splice scope children
         let count = add.len();
-        let removed = self.children.splice(offset .. offset + remove, add);
+        let removed = self.children.splice(offset .. offset + remove, add).collect::<Vec<_>>();
 
         // Clear parent state for removed children
-        for child in removed {
-            let mut child = child.0.borrow_mut();
+        for child in &removed {
+            let mut child = child.inner_mut();
             child.parent = None;
             child.index_in_parent = 0;
         }
 
         // Update parent state for old children after new children
         for i in offset + count .. self.children.len() {
-            self.children[i].0.borrow_mut().index_in_parent = i;
+            self.children[i].inner_mut().index_in_parent = i;
         }
+        return removed;
     }
 
-    fn clear(&mut self) {
+    fn move_child(&mut self, from: usize, to: usize) {
+        batch::flush_el(self.batch_key());
+        if from >= self.children.len() || to >= self.children.len() {
+            report_error(JsValue::from_str("Move child index is outside of the element's children"));
+            return;
+        }
+        if from == to {
+            return;
+        }
+        let child = self.children.remove(from);
+        self.children.insert(to, child);
+        let insert_ref = self.insert_ref(to + 1);
+        let child_el = self.children[to].inner().el.clone();
+        self.el.insert_before(&child_el, insert_ref.as_ref()).or_report();
+        for i in from.min(to) ..= from.max(to) {
+            self.children[i].inner_mut().index_in_parent = i;
+        }
+    }
+
+    /// Returns the removed children, see `splice`.
+    #[must_use]
+    fn resync(&mut self, self2: &Rc<RefCell<El_>>, policy: ResyncPolicy) -> Vec<El> {
+        batch::flush_el(self.batch_key());
+        self.text_cache = None;
+        let dom = self.el.children();
+        let mut old = std::mem::take(&mut self.children);
+        let mut foreign = vec![];
+        for node in (0 .. dom.length()).filter_map(|i| dom.item(i)) {
+            if let Some(i) = old.iter().position(|c| c.inner().el == node) {
+                self.children.push(old.remove(i));
+                continue;
+            }
+            match policy {
+                ResyncPolicy::Adopt => {
+                    let adopted = el_from_raw(node);
+                    adopted.inner_mut().parent = Some(Rc::downgrade(self2));
+                    self.children.push(adopted);
+                },
+                ResyncPolicy::RemoveForeign => {
+                    foreign.push(node);
+                },
+            }
+        }
+        for node in foreign {
+            node.remove();
+        }
+        for (i, child) in self.children.iter().enumerate() {
+            child.inner_mut().index_in_parent = i;
+        }
+        for child in &old {
+            let mut child = child.inner_mut();
+            child.parent = None;
+            child.index_in_parent = 0;
+        }
+        return old;
+    }
+
+    /// Returns the removed children, see `splice`.
+    #[must_use]
+    fn clear(&mut self) -> Vec<El> {
+        batch::flush_el(self.batch_key());
+        self.text_cache = None;
         self.el.set_text_content(None);
-        self.children.clear();
+        return std::mem::take(&mut self.children);
     }
 
     fn extend(&mut self, self2: &Rc<RefCell<El_>>, add: Vec<El>) {
+        batch::flush_el(self.batch_key());
+        self.text_cache = None;
         let offset = self.children.len();
         for (i, child) in add.iter().enumerate() {
-            let mut c = child.0.borrow_mut();
+            let mut c = child.inner_mut();
             c.parent = Some(Rc::downgrade(self2));
             c.index_in_parent = offset + i;
-            self.el.append_child(&c.el).unwrap();
+            self.el.append_child(&c.el).or_report();
         }
         self.children.extend(add);
     }
 }
 
+/// Compares formatted output against an existing string, failing on the first
+/// difference.
+struct FmtEq<'a> {
+    rest: &'a str,
+}
+
+impl fmt::Write for FmtEq<'_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let Some(rest) = self.rest.strip_prefix(s) else {
+            return Err(fmt::Error);
+        };
+        self.rest = rest;
+        return Ok(());
+    }
+}
+
+/// Set an element's text with format arguments, skipping the DOM write if it's
+/// unchanged: `text!(el, "{} items", n)`.  See `El::ref_text_fmt`.
+#[macro_export]
+macro_rules! text{
+    ($el: expr, $($arg: tt)*) => {
+        $el.ref_text_fmt(format_args!($($arg)*))
+    };
+}
+
+/// What `El::ref_resync` does with DOM children that aren't scoped children.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ResyncPolicy {
+    /// Wrap them as new scoped children (see `el_from_raw`).
+    Adopt,
+    /// Remove them from the DOM.
+    RemoveForeign,
+}
+
+/// Text direction, for `El::dir`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Dir {
+    Ltr,
+    Rtl,
+    Auto,
+}
+
+impl Dir {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Dir::Ltr => return "ltr",
+            Dir::Rtl => return "rtl",
+            Dir::Auto => return "auto",
+        }
+    }
+}
+
 /// An html element with associated data sharing the same lifetime.
 ///
 /// There are a number of `ref_` and non-`ref_` method pairs. The non-`ref_`
@@ -101,131 +353,409 @@ impl El_ {
 /// `El` values are clonable. Note that if you store a parent element in a child
 /// element you'll end up with a reference cycle and the subtree will never be
 /// freed.  You can use `weak()` to get a weak reference if you want to do this.
+///
+/// Callbacks and drop code (ex: `own` suppliers, values owned by removed children)
+/// never run while the element's state is borrowed, so they can use the element
+/// freely.  The exception is DOM events fired synchronously during a modification
+/// (ex: `focusout` when a focused child is removed) - an element can't be modified
+/// from these while it's being modified.  This panics with a message explaining the
+/// problem; schedule the change to happen afterwards instead (ex: with
+/// `schedule::timeout(0, ...)`).
 #[derive(Clone)]
 pub struct El(pub(crate) Rc<RefCell<El_>>);
 
+const REENTRANT_MESSAGE: &str =
+    "Tried to access a rooting element while it's being modified. This happens when code running during a modification of an element (ex: an event fired synchronously by the DOM while removing a child) modifies the same element. Schedule the change to run afterwards instead (ex: with `schedule::timeout(0, ...)`).";
+
 impl El {
+    /// Borrow the element state, panicking with an explanation if it's being
+    /// modified.
+    fn inner(&self) -> Ref<'_, El_> {
+        let Ok(s) = self.0.try_borrow() else {
+            panic!("{}", REENTRANT_MESSAGE);
+        };
+        return s;
+    }
+
+    /// Mutably borrow the element state, panicking with an explanation if it's
+    /// already borrowed.
+    fn inner_mut(&self) -> RefMut<'_, El_> {
+        let Ok(s) = self.0.try_borrow_mut() else {
+            panic!("{}", REENTRANT_MESSAGE);
+        };
+        return s;
+    }
+
+    /// Perform a write on the raw element, deferred if a batch is in progress (see
+    /// `batch`).
+    pub(crate) fn write(&self, f: impl FnOnce(&Element) + 'static) {
+        let (key, el) = {
+            let s = self.inner();
+            (s.batch_key(), s.el.clone())
+        };
+        batch::write(key, el, f);
+    }
+
     /// Set text contents.
     pub fn text(self, text: &str) -> Self {
-        self.0.borrow().el.set_text_content(Some(text));
+        self.ref_text(text);
         return self;
     }
 
     pub fn ref_text(&self, text: &str) -> &Self {
-        self.0.borrow().el.set_text_content(Some(text));
+        let text = text.to_string();
+        self.inner_mut().text_cache = Some(text.clone());
+        self.write(move |e| e.set_text_content(Some(&text)));
+        return self;
+    }
+
+    /// Set text contents from format arguments (see the `text!` macro).  If the
+    /// formatted text is the same as the text last set this way (or with `text`) it's
+    /// compared in place without allocating and the DOM isn't touched.
+    pub fn text_fmt(self, args: fmt::Arguments) -> Self {
+        self.ref_text_fmt(args);
         return self;
     }
 
+    /// Like `ref_text` but does nothing if the text is the same as the text last set
+    /// via this `El`.  Changes made through `raw()` aren't detected.
+    pub fn ref_text_if_changed(&self, text: &str) -> &Self {
+        if self.inner().text_cache.as_deref() == Some(text) {
+            return self;
+        }
+        return self.ref_text(text);
+    }
+
+    pub fn ref_text_fmt(&self, args: fmt::Arguments) -> &Self {
+        if let Some(cached) = &self.inner().text_cache {
+            let mut cmp = FmtEq { rest: cached };
+            if fmt::write(&mut cmp, args).is_ok() && cmp.rest.is_empty() {
+                return self;
+            }
+        }
+        return self.ref_text(&fmt::format(args));
+    }
+
     /// Set the element id.
     pub fn id(self, id: &str) -> Self {
-        self.0.borrow().el.set_id(id);
+        let id = id.to_string();
+        self.write(move |e| e.set_id(&id));
+        return self;
+    }
+
+    /// Set the text direction (`dir` attribute).
+    pub fn dir(self, dir: Dir) -> Self {
+        self.ref_dir(dir);
         return self;
     }
 
+    pub fn ref_dir(&self, dir: Dir) -> &Self {
+        return self.ref_attr("dir", dir.as_str());
+    }
+
+    /// Set the language of the element's contents (`lang` attribute), ex: `en-US`.
+    pub fn lang(self, lang: &str) -> Self {
+        self.ref_lang(lang);
+        return self;
+    }
+
+    pub fn ref_lang(&self, lang: &str) -> &Self {
+        return self.ref_attr("lang", lang);
+    }
+
     /// Set an arbitrary attribute.  Note there are special methods for setting `class`
     /// and `id` which may afford safer workflows.
     pub fn attr(self, key: &str, value: &str) -> Self {
-        self.0.borrow().el.set_attribute(key, value).unwrap();
+        self.ref_attr(key, value);
         return self;
     }
 
     pub fn ref_attr(&self, key: &str, value: &str) -> &Self {
-        self.0.borrow().el.set_attribute(key, value).unwrap();
+        let key = key.to_string();
+        let value = value.to_string();
+        self.inner_mut().attr_cache.insert(key.clone(), value.clone());
+        self.write(move |e| {
+            e.set_attribute(&key, &value).or_report();
+        });
         return self;
     }
 
+    /// Like `ref_attr` but does nothing if the value is the same as the value last set
+    /// via this `El`.  Changes made through `raw()` aren't detected.
+    pub fn ref_attr_if_changed(&self, key: &str, value: &str) -> &Self {
+        if self.inner().attr_cache.get(key).map(|v| v.as_str()) == Some(value) {
+            return self;
+        }
+        return self.ref_attr(key, value);
+    }
+
+    /// Like `attr` but returns an error (ex: for an invalid attribute name) rather
+    /// than going to the error handler.  This is never deferred by `batch`; writes
+    /// already queued for the element are applied first so they keep their order.
+    pub fn try_attr(self, key: &str, value: &str) -> Result<Self, JsValue> {
+        self.ref_try_attr(key, value)?;
+        return Ok(self);
+    }
+
+    pub fn ref_try_attr(&self, key: &str, value: &str) -> Result<&Self, JsValue> {
+        let mut s = self.inner_mut();
+
+        // Applied immediately to return the error, so apply earlier queued writes first
+        // to keep them from overwriting this
+        batch::flush_el(s.batch_key());
+        s.el.set_attribute(key, value)?;
+        s.attr_cache.insert(key.to_string(), value.to_string());
+        drop(s);
+        return Ok(self);
+    }
+
+    /// Set a javascript property on the element (ex: `value`, `checked`, or custom
+    /// element properties), as opposed to an html attribute.
+    pub fn prop(self, key: &str, value: impl Into<JsValue>) -> Self {
+        self.ref_prop(key, value);
+        return self;
+    }
+
+    pub fn ref_prop(&self, key: &str, value: impl Into<JsValue>) -> &Self {
+        let key = JsValue::from_str(key);
+        let value = value.into();
+        self.write(move |e| {
+            Reflect::set(e, &key, &value).or_report();
+        });
+        return self;
+    }
+
+    /// Get a javascript property of the element.  Returns `undefined` if the property
+    /// doesn't exist.
+    pub fn ref_get_prop(&self, key: &str) -> JsValue {
+        return Reflect::get(&self.raw(), &JsValue::from_str(key)).unwrap_or(JsValue::UNDEFINED);
+    }
+
     /// Remove an attribute from the element.
     pub fn ref_remove_attr(&self, key: &str) -> &Self {
-        self.0.borrow().el.remove_attribute(key).unwrap();
+        self.inner_mut().attr_cache.remove(key);
+        let key = key.to_string();
+        self.write(move |e| {
+            e.remove_attribute(&key).or_report();
+        });
         return self;
     }
 
     /// Add (if not existing) all of the listed keys.
     pub fn classes(self, keys: &[&str]) -> Self {
-        let c = self.0.borrow().el.class_list();
-        for k in keys {
-            c.add_1(k).unwrap();
-        }
+        self.ref_classes(keys);
         return self;
     }
 
     pub fn ref_classes(&self, keys: &[&str]) -> &Self {
-        let c = self.0.borrow().el.class_list();
-        for k in keys {
-            c.add_1(k).unwrap();
-        }
-        return self;
+        return self.ref_modify_classes(&keys.iter().map(|k| (*k, true)).collect::<Vec<_>>());
     }
 
     /// Remove (if not existing) all of the listed keys.
     pub fn ref_remove_classes(&self, keys: &[&str]) -> &Self {
-        let c = self.0.borrow().el.class_list();
-        for k in keys {
-            c.remove_1(k).unwrap();
-        }
-        return self;
+        return self.ref_modify_classes(&keys.iter().map(|k| (*k, false)).collect::<Vec<_>>());
     }
 
     pub fn ref_modify_classes(&self, keys: &[(&str, bool)]) -> &Self {
-        let c = self.0.borrow().el.class_list();
-        for (k, on) in keys {
-            if *on {
-                c.add_1(k).unwrap();
-            } else {
-                c.remove_1(k).unwrap();
+        let keys = keys.iter().map(|(k, on)| (k.to_string(), *on)).collect::<Vec<_>>();
+        self.write(move |e| {
+            let c = e.class_list();
+            for (k, on) in keys {
+                if on {
+                    c.add_1(&k).or_report();
+                } else {
+                    c.remove_1(&k).or_report();
+                }
             }
-        }
+        });
+        return self;
+    }
+
+    /// Make the class list exactly the listed keys, adding missing and removing extra
+    /// classes.  Classes starting with `protect_prefix` are left as is (ex: for
+    /// classes managed elsewhere, like by `ref_scoped_class`).
+    pub fn set_classes<'a>(self, keys: impl IntoIterator<Item = &'a str>, protect_prefix: Option<&str>) -> Self {
+        self.ref_set_classes(keys, protect_prefix);
+        return self;
+    }
+
+    pub fn ref_set_classes<'a>(
+        &self,
+        keys: impl IntoIterator<Item = &'a str>,
+        protect_prefix: Option<&str>,
+    ) -> &Self {
+        let keys = keys.into_iter().map(|k| k.to_string()).collect::<HashSet<_>>();
+        let protect_prefix = protect_prefix.map(|p| p.to_string());
+        self.write(move |e| {
+            let c = e.class_list();
+            let existing = (0 .. c.length()).filter_map(|i| c.item(i)).collect::<Vec<_>>();
+            for k in &existing {
+                if keys.contains(k) {
+                    continue;
+                }
+                if protect_prefix.as_ref().is_some_and(|p| k.starts_with(p.as_str())) {
+                    continue;
+                }
+                c.remove_1(k).or_report();
+            }
+            for k in keys {
+                if !existing.contains(&k) {
+                    c.add_1(&k).or_report();
+                }
+            }
+        });
         return self;
     }
 
     /// Add a single element to the end.
     pub fn push(self, add: El) -> Self {
-        self.0.borrow_mut().extend(&self.0, vec![add]);
+        self.ref_push(add);
         return self;
     }
 
     pub fn ref_push(&self, add: El) -> &Self {
-        self.0.borrow_mut().extend(&self.0, vec![add]);
+        measure("push", self, || self.inner_mut().extend(&self.0, vec![add]));
         return self;
     }
 
     /// Add multiple elements to the end.
     pub fn extend(self, add: Vec<El>) -> Self {
-        self.0.borrow_mut().extend(&self.0, add);
+        self.ref_extend(add);
         return self;
     }
 
     pub fn ref_extend(&self, add: Vec<El>) -> &Self {
-        self.0.borrow_mut().extend(&self.0, add);
+        measure("extend", self, || self.inner_mut().extend(&self.0, add));
         return self;
     }
 
     /// Add and remove multiple elements.
+    ///
+    /// Panics with a description of the problem if the range is outside of the
+    /// children.  See `ref_try_splice` for a non-panicking version.
     pub fn ref_splice(&self, offset: usize, remove: usize, add: Vec<El>) -> &Self {
-        self.0.borrow_mut().splice(&self.0, offset, remove, add);
+        if let Err(e) = self.inner().check_splice_range(offset, remove) {
+            panic!("{}", e);
+        }
+        measure("splice", self, || {
+            let removed = self.inner_mut().splice(&self.0, offset, remove, add);
+            drop(removed);
+        });
+        return self;
+    }
+
+    /// Like `ref_splice` but returns an error describing the problem, without making
+    /// any changes, if the range is outside of the children or some scoped children
+    /// were detached from this element outside of rooting (see `ref_resync`).
+    pub fn ref_try_splice(&self, offset: usize, remove: usize, add: Vec<El>) -> Result<&Self, JsValue> {
+        {
+            let s = self.inner();
+            s.check_splice_range(offset, remove).map_err(|e| JsValue::from_str(&e))?;
+            s.check_dom_children().map_err(|e| JsValue::from_str(&e))?;
+        }
+        return Ok(self.ref_splice(offset, remove, add));
+    }
+
+    /// Make the scoped children match the element's actual DOM children, if they
+    /// were changed outside of rooting (ex: by a browser extension or third party
+    /// script).  Scoped children no longer in the DOM are dropped, and DOM children
+    /// rooting doesn't know about are handled according to `policy`.
+    pub fn ref_resync(&self, policy: ResyncPolicy) -> &Self {
+        let removed = self.inner_mut().resync(&self.0, policy);
+        drop(removed);
+        return self;
+    }
+
+    /// Move the child at `from` so it ends up at index `to`, with a single DOM move.
+    /// Unlike removing and re-adding the child, this never drops it.
+    pub fn ref_move_child(&self, from: usize, to: usize) -> &Self {
+        measure("move", self, || self.inner_mut().move_child(from, to));
         return self;
     }
 
+    /// The number of values owned by the element (see `own`), including listeners.
+    #[cfg(feature = "inspector")]
+    pub(crate) fn owned_count(&self) -> usize {
+        return self.inner().local.len();
+    }
+
+    pub(crate) fn set_pool(&self, pool: WeakPool) {
+        self.inner_mut().pool = Some(pool);
+    }
+
+    /// Replace (or with `None`, remove) the owned value for `key`, dropping the
+    /// previous one.  For state that should only exist once per element.
+    pub(crate) fn set_owned_slot(&self, key: &'static str, value: Option<ScopeValue>) {
+        let old = {
+            let mut s = self.inner_mut();
+            match value {
+                Some(value) => s.owned_slots.insert(key, value),
+                None => s.owned_slots.remove(key),
+            }
+        };
+        drop(old);
+    }
+
+    /// The scoped children.
+    pub(crate) fn children(&self) -> Vec<El> {
+        return self.inner().children.clone();
+    }
+
+    /// The index of `child` in this element's children, if it's a child.  Children
+    /// are compared by identity.
+    pub fn ref_index_of(&self, child: &El) -> Option<usize> {
+        return child.index_in(self);
+    }
+
+    /// Remove `child` from this element's children, returning `false` if it's not a
+    /// child.
+    pub fn ref_remove_child(&self, child: &El) -> bool {
+        let Some(i) = self.ref_index_of(child) else {
+            return false;
+        };
+        self.ref_splice(i, 1, vec![]);
+        return true;
+    }
+
     /// Remove all children.
     pub fn ref_clear(&self) -> &Self {
-        self.0.borrow_mut().clear();
+        measure("clear", self, || {
+            let removed = self.inner_mut().clear();
+            drop(removed);
+        });
         return self;
     }
 
     /// Attach the value to this scope, so it doesn't get dropped until the element is
     /// removed from the tree.
     pub fn own<T: 'static>(self, supplier: impl FnOnce(&El) -> T) -> Self {
-        let res = supplier(&self);
-        self.0.borrow_mut().local.push(scope_any(res));
+        self.ref_own(supplier);
         return self;
     }
 
     pub fn ref_own<T: 'static>(&self, supplier: impl FnOnce(&El) -> T) -> &Self {
-        let res = supplier(&self);
-        self.0.borrow_mut().local.push(scope_any(res));
+        let res = supplier(self);
+        trace("own", self);
+        self.inner_mut().local.push(scope_any(res));
+        return self;
+    }
+
+    /// Label the element for debugging, ex: in trace logs with the `log` feature.
+    pub fn debug_name(self, name: &str) -> Self {
+        self.ref_debug_name(name);
         return self;
     }
 
+    pub fn ref_debug_name(&self, name: &str) -> &Self {
+        self.inner_mut().debug_name = Some(name.to_string());
+        return self;
+    }
+
+    /// The label set with `ref_debug_name`, if any.
+    pub fn ref_get_debug_name(&self) -> Option<String> {
+        return self.inner().debug_name.clone();
+    }
+
     pub fn on(self, event: &'static str, cb: impl FnMut(&Event) + 'static) -> Self {
         self.ref_on(event, cb);
         return self;
@@ -242,7 +772,7 @@ impl El {
     }
 
     pub fn ref_on(&self, event: &'static str, cb: impl FnMut(&Event) + 'static) -> &Self {
-        let mut s = self.0.borrow_mut();
+        let mut s = self.inner_mut();
         let listener = EventListener::new(&s.el, event, cb);
         s.local.push(scope_any(listener));
         drop(s);
@@ -255,7 +785,7 @@ impl El {
         opts: EventListenerOptions,
         cb: impl FnMut(&Event) + 'static,
     ) -> &Self {
-        let mut s = self.0.borrow_mut();
+        let mut s = self.inner_mut();
         let listener = EventListener::new_with_options(&s.el, event, opts, cb);
         s.local.push(scope_any(listener));
         drop(s);
@@ -288,34 +818,187 @@ impl El {
         });
     }
 
+    /// Call `cb` every `ms` milliseconds until the element is dropped.
+    #[cfg(feature = "futures")]
+    pub fn every(self, ms: u32, cb: impl FnMut(El) + 'static) -> Self {
+        self.ref_every(ms, cb);
+        return self;
+    }
+
+    #[cfg(feature = "futures")]
+    pub fn ref_every(&self, ms: u32, mut cb: impl FnMut(El) + 'static) -> &Self {
+        return self.ref_own(move |e: &El| {
+            let e = e.weak();
+            return own_stream(ticker(ms), move |_| {
+                let Some(e) = e.upgrade() else {
+                    return;
+                };
+                cb(e);
+            });
+        });
+    }
+
     /// Add a listener for an event. The listener will be detached when this element is
     /// dropped (removed from the tree).
     pub fn ref_listen(&self, event: &'static str, cb: impl FnMut(&Event) + 'static) -> &Self {
-        let mut s = self.0.borrow_mut();
+        let mut s = self.inner_mut();
         let listener = EventListener::new(&s.el, event, cb);
         s.local.push(scope_any(listener));
         drop(s);
         return self;
     }
 
-    /// Replace the element in its parent with zero or more new elements.
-    pub fn ref_replace(&self, other: Vec<El>) {
+    /// Replace the element in its parent with zero or more new elements.  Returns
+    /// `None` and does nothing if the element has no parent.
+    ///
+    /// The element itself is only removed from the tree, so if you hold another
+    /// reference to it it stays intact and can be put back later, ex: with the
+    /// returned handle, which can swap the content at this position again any number
+    /// of times.
+    pub fn ref_replace(&self, other: Vec<El>) -> Option<ReplacedHandle> {
         let parent;
         let index_in_parent;
         {
-            let self1 = self.0.borrow();
+            let self1 = self.inner();
             index_in_parent = self1.index_in_parent;
-            let Some(parent1) = self1.parent.as_ref().and_then(|p| p.upgrade()) else {
-                return;
+            parent = El(self1.parent.as_ref().and_then(|p| p.upgrade())?);
+        }
+        let before = match index_in_parent.checked_sub(1) {
+            Some(i) => parent.inner().children.get(i).map(|e| e.weak()),
+            None => None,
+        };
+        let current = other.iter().map(|e| e.weak()).collect();
+        parent.ref_splice(index_in_parent, 1, other);
+        return Some(ReplacedHandle {
+            parent: parent.weak(),
+            before,
+            index: index_in_parent,
+            current,
+        });
+    }
+
+    /// Whether this element is currently a scoped child of another element.
+    pub(crate) fn has_parent(&self) -> bool {
+        return self.inner().parent.as_ref().is_some_and(|p| p.strong_count() > 0);
+    }
+
+    /// This element's index in `parent`, if it's a child of `parent`.
+    pub(crate) fn index_in(&self, parent: &El) -> Option<usize> {
+        let s = self.inner();
+        if s.parent.as_ref().is_none_or(|p| p.as_ptr() != Rc::as_ptr(&parent.0)) {
+            return None;
+        }
+        return Some(s.index_in_parent);
+    }
+
+    /// Remove the element from its parent (and the DOM) without dropping it.  Anything
+    /// owned by the element (listeners, tasks) stays alive and keeps running, although
+    /// DOM events won't reach the element while it's detached.  Use
+    /// `DetachedEl::reattach` to put it back somewhere, or drop the `DetachedEl` to
+    /// drop the element.
+    pub fn detach_keepalive(&self) -> DetachedEl {
+        self.ref_replace(vec![]);
+        self.raw().remove();
+        return DetachedEl(self.clone());
+    }
+
+    /// The top-most ancestor of this element, following the element tree.
+    fn top(&self) -> El {
+        let mut at = self.clone();
+        loop {
+            let Some(parent) = at.inner().parent.as_ref().and_then(|p| p.upgrade()) else {
+                break;
+            };
+            at = El(parent);
+        }
+        return at;
+    }
+
+    /// Whether the element is in a tree mounted by a `Root` (ex: `set_root`).  This
+    /// follows the element tree rather than querying the DOM, so it doesn't know
+    /// about elements attached with raw DOM methods.
+    pub fn is_connected(&self) -> bool {
+        return self.top().inner().mounted;
+    }
+
+    /// The child indexes to follow from the top-most ancestor to reach this element.
+    /// This is empty for an element without a parent.
+    pub fn path_from_root(&self) -> Vec<usize> {
+        let mut path = vec![];
+        let mut at = self.clone();
+        loop {
+            let parent = {
+                let at1 = at.inner();
+                let Some(parent) = at1.parent.as_ref().and_then(|p| p.upgrade()) else {
+                    break;
+                };
+                path.push(at1.index_in_parent);
+                parent
             };
-            parent = parent1;
+            at = El(parent);
         }
-        El(parent).ref_splice(index_in_parent, 1, other);
+        path.reverse();
+        return path;
+    }
+
+    /// Visit scoped descendants depth first (in document order), stopping when `f`
+    /// returns `false`.
+    fn walk_descendants(&self, f: &mut impl FnMut(&El) -> bool) -> bool {
+        let children = self.inner().children.clone();
+        for child in &children {
+            if !f(child) || !child.walk_descendants(f) {
+                return false;
+            }
+        }
+        return true;
+    }
+
+    /// Find the first descendant matching the css selector and return the scoped
+    /// element for it.  Returns `None` if nothing matches or the matching node isn't
+    /// a scoped descendant (ex: it was created with raw DOM methods).  An invalid
+    /// selector goes to the error handler.
+    pub fn ref_find(&self, selector: &str) -> Option<El> {
+        let found = self.raw().query_selector(selector).or_report()??;
+        let mut out = None;
+        self.walk_descendants(&mut |e| {
+            if e.inner().el == found {
+                out = Some(e.clone());
+                return false;
+            }
+            return true;
+        });
+        return out;
+    }
+
+    /// Like `ref_find` but returns all scoped descendants matching the selector, in
+    /// document order.
+    pub fn ref_find_all(&self, selector: &str) -> Vec<El> {
+        let Some(found) = self.raw().query_selector_all(selector).or_report() else {
+            return vec![];
+        };
+        let mut found = (0 .. found.length()).filter_map(|i| found.get(i)).collect::<Vec<_>>();
+        let mut out = vec![];
+        self.walk_descendants(&mut |e| {
+            let node: &Node = &e.inner().el;
+            if let Some(i) = found.iter().position(|f| f == node) {
+                found.swap_remove(i);
+                out.push(e.clone());
+            }
+            return !found.is_empty();
+        });
+        return out;
+    }
+
+    /// Call `f` with the wrapped web_sys element.  The element state isn't borrowed
+    /// while `f` runs, so `f` can use this `El` freely.
+    pub fn with_raw<T>(&self, f: impl FnOnce(&Element) -> T) -> T {
+        let el = self.inner().el.clone();
+        return f(&el);
     }
 
     /// Get the wrapped web_sys element from the El.
     pub fn raw(&self) -> Element {
-        return self.0.borrow().el.clone();
+        return self.inner().el.clone();
     }
 
     /// For debugging, an id based on pointer address
@@ -327,6 +1010,140 @@ impl El {
     pub fn weak(&self) -> WeakEl {
         return WeakEl(Rc::downgrade(&self.0));
     }
+
+    /// Register `cb` to be called with each copy of this element made by
+    /// `deep_clone` with `CloneOwnedPolicy::RunHooks`, ex: to attach listeners to the
+    /// copy.  Copies inherit the hooks, so copies of copies get them too.
+    pub fn on_deep_clone(self, cb: impl Fn(&El) + 'static) -> Self {
+        self.ref_on_deep_clone(cb);
+        return self;
+    }
+
+    pub fn ref_on_deep_clone(&self, cb: impl Fn(&El) + 'static) -> &Self {
+        self.inner_mut().clone_hooks.push(Rc::new(cb));
+        return self;
+    }
+
+    /// Make a new element hierarchy copying this element and its scoped descendants
+    /// (ex: to stamp out copies of a prototype row).  DOM nodes are cloned, but values
+    /// owned by the elements (listeners, tasks) aren't - see `CloneOwnedPolicy` for
+    /// re-attaching them.
+    ///
+    /// Elements without scoped children are cloned with all their DOM contents, but
+    /// for elements with scoped children only the scoped children are copied: other
+    /// child nodes (ex: added via `raw()`) are left out.
+    ///
+    /// If cloning fails the error goes to the error handler (see
+    /// `set_error_handler`) and an empty `div` is returned instead.
+    pub fn deep_clone(&self, clone_owned: CloneOwnedPolicy) -> El {
+        let (raw, children, text_cache, attr_cache, debug_name, clone_hooks) = {
+            let s = self.inner();
+            (
+                s.el.clone(),
+                s.children.clone(),
+                s.text_cache.clone(),
+                s.attr_cache.clone(),
+                s.debug_name.clone(),
+                s.clone_hooks.clone(),
+            )
+        };
+        let Some(copy) = raw.clone_node_with_deep(children.is_empty()).or_report() else {
+            return el("div");
+        };
+        let copy = el_from_raw(copy.unchecked_into());
+        {
+            let mut c = copy.inner_mut();
+            c.text_cache = text_cache;
+            c.attr_cache = attr_cache;
+            c.debug_name = debug_name;
+            c.clone_hooks = clone_hooks.clone();
+        }
+        copy.ref_extend(children.iter().map(|c| c.deep_clone(clone_owned)).collect());
+        if clone_owned == CloneOwnedPolicy::RunHooks {
+            for hook in clone_hooks {
+                hook(&copy);
+            }
+        }
+        return copy;
+    }
+}
+
+/// What `El::deep_clone` does about values owned by the copied elements.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CloneOwnedPolicy {
+    /// The copies own nothing.
+    Skip,
+    /// Call hooks registered with `El::ref_on_deep_clone` on each copy, after its
+    /// children are copied.
+    RunHooks,
+}
+
+/// An element removed from the tree with `El::detach_keepalive`, kept alive until
+/// reattached or dropped.
+pub struct DetachedEl(El);
+
+impl DetachedEl {
+    pub fn el(&self) -> &El {
+        return &self.0;
+    }
+
+    /// Insert the element into `parent` at `index`, returning it.
+    pub fn reattach(self, parent: &El, index: usize) -> El {
+        parent.ref_splice(index, 0, vec![self.0.clone()]);
+        return self.0;
+    }
+}
+
+/// The position of an element replaced with `El::ref_replace`, for swapping in
+/// different content later (ex: switching views).
+pub struct ReplacedHandle {
+    parent: WeakEl,
+    /// The sibling before the position, to find the position again if the current
+    /// content is empty or was moved.
+    before: Option<WeakEl>,
+    /// Fallback position if the sibling is gone too.
+    index: usize,
+    current: Vec<WeakEl>,
+}
+
+impl ReplacedHandle {
+    /// The parent containing the replaced position, if it's still alive.
+    pub fn parent(&self) -> Option<El> {
+        return self.parent.upgrade();
+    }
+
+    /// The elements currently at the position (the ones from the last replace that
+    /// are still in the parent).
+    pub fn current(&self) -> Vec<El> {
+        let Some(parent) = self.parent.upgrade() else {
+            return vec![];
+        };
+        return self
+            .current
+            .iter()
+            .filter_map(|e| e.upgrade())
+            .filter(|e| e.index_in(&parent).is_some())
+            .collect();
+    }
+
+    /// Replace the current elements at the position with `other`.  Does nothing if
+    /// the parent has been dropped.
+    pub fn replace(&mut self, other: Vec<El>) {
+        let Some(parent) = self.parent.upgrade() else {
+            return;
+        };
+        let current = self.current();
+        let offset = match current.first() {
+            Some(first) => first.index_in(&parent).unwrap(),
+            None => match self.before.as_ref().and_then(|b| b.upgrade()).and_then(|b| b.index_in(&parent)) {
+                Some(i) => i + 1,
+                None => self.index.min(parent.inner().children.len()),
+            },
+        };
+        self.index = offset;
+        self.current = other.iter().map(|e| e.weak()).collect();
+        parent.ref_splice(offset, current.len(), other);
+    }
 }
 
 #[derive(Clone)]
@@ -338,25 +1155,46 @@ impl WeakEl {
     }
 }
 
-/// Create a new element.
+/// Create a new element in the current document (see `with_document`).  If the
+/// tag is invalid and an error handler is set (see `set_error_handler`) the error
+/// is reported and a `div` is created instead.
 pub fn el(tag: &str) -> El {
-    return El(Rc::new(RefCell::new(El_ {
-        el: document().create_element(tag).unwrap(),
-        parent: None,
-        index_in_parent: 0,
-        children: vec![],
-        local: vec![],
-    })));
+    match try_el(tag) {
+        Ok(e) => return e,
+        Err(e) => {
+            report_error(e);
+            return el_from_raw(current_document().create_element("div").unwrap());
+        },
+    }
+}
+
+/// Create a new element in `doc`, for instance the document of another window or
+/// an iframe.  See `el`.
+pub fn el_in(doc: &Document, tag: &str) -> El {
+    return with_document(doc, || el(tag));
+}
+
+/// Create a new element, returning an error if the element can't be created (ex:
+/// the tag is invalid).
+pub fn try_el(tag: &str) -> Result<El, JsValue> {
+    return Ok(el_from_raw(current_document().create_element(tag)?));
 }
 
 /// Create a new scoped element from an element passed in (ex: for existing
 /// elements, or namespaced elements set up specially).
 pub fn el_from_raw(el: Element) -> El {
     return El(Rc::new(RefCell::new(El_ {
-        el: el,
+        el,
         parent: None,
         index_in_parent: 0,
         children: vec![],
         local: vec![],
+        mounted: false,
+        text_cache: None,
+        attr_cache: HashMap::new(),
+        debug_name: None,
+        clone_hooks: vec![],
+        pool: None,
+        owned_slots: HashMap::new(),
     })));
 }
@@ -0,0 +1,55 @@
+use std::{
+    cell::RefCell,
+    rc::Rc,
+};
+use wasm_bindgen::JsValue;
+
+type ErrorHandler = Rc<dyn Fn(&JsValue)>;
+
+thread_local!{
+    static ERROR_HANDLER: RefCell<Option<ErrorHandler>> = const {
+        RefCell::new(None)
+    };
+}
+
+/// Set a handler for errors from DOM operations done by this crate (like setting
+/// an invalid attribute name, or mounting when the mount point is missing).
+///
+/// By default such errors panic.  With a handler set, the handler is called with
+/// the error instead and the operation is skipped, so you can downgrade failures
+/// to logged errors in production.  Where a value must be produced regardless (ex:
+/// `el()` with an invalid tag) a best-effort substitute is used - see the
+/// individual methods.
+pub fn set_error_handler(cb: impl Fn(&JsValue) + 'static) {
+    ERROR_HANDLER.with(|h| *h.borrow_mut() = Some(Rc::new(cb)));
+}
+
+/// Remove the error handler, restoring the default panicking behavior.
+pub fn clear_error_handler() {
+    ERROR_HANDLER.with(|h| *h.borrow_mut() = None);
+}
+
+/// Send an error to the error handler, or panic if there's none.
+pub(crate) fn report_error(e: JsValue) {
+    let Some(handler) = ERROR_HANDLER.with(|h| h.borrow().clone()) else {
+        panic!("{:?}", e);
+    };
+    handler(&e);
+}
+
+pub(crate) trait ReportExt<T> {
+    /// Send the error (if any) to the error handler, continuing with `None`.
+    fn or_report(self) -> Option<T>;
+}
+
+impl<T> ReportExt<T> for Result<T, JsValue> {
+    fn or_report(self) -> Option<T> {
+        match self {
+            Ok(v) => return Some(v),
+            Err(e) => {
+                report_error(e);
+                return None;
+            },
+        }
+    }
+}
@@ -0,0 +1,71 @@
+use wasm_bindgen::JsCast;
+use web_sys::{
+    DragEvent,
+    File,
+    FileList,
+    HtmlInputElement,
+};
+use crate::El;
+#[cfg(feature = "futures")]
+use {
+    js_sys::Uint8Array,
+    wasm_bindgen::JsValue,
+    wasm_bindgen_futures::JsFuture,
+};
+
+/// Collect the files in a `FileList` (ex: from `DataTransfer::files` or
+/// `HtmlInputElement::files`), which may be missing.
+pub fn file_list(files: Option<FileList>) -> Vec<File> {
+    let Some(files) = files else {
+        return vec![];
+    };
+    return (0 .. files.length()).filter_map(|i| files.get(i)).collect();
+}
+
+impl El {
+    /// Call `cb` with the files dropped onto the element.  This handles preventing
+    /// the browser's default handling of dragged files (opening them).
+    pub fn on_file_drop(self, cb: impl FnMut(Vec<File>) + 'static) -> Self {
+        self.ref_on_file_drop(cb);
+        return self;
+    }
+
+    pub fn ref_on_file_drop(&self, mut cb: impl FnMut(Vec<File>) + 'static) -> &Self {
+        self.ref_on("dragover", |ev| {
+            ev.prevent_default();
+            if let Some(transfer) = ev.dyn_ref::<DragEvent>().and_then(|ev| ev.data_transfer()) {
+                transfer.set_drop_effect("copy");
+            }
+        });
+        return self.ref_on("drop", move |ev| {
+            ev.prevent_default();
+            let files = file_list(ev.dyn_ref::<DragEvent>().and_then(|ev| ev.data_transfer()).and_then(|t| t.files()));
+            if !files.is_empty() {
+                cb(files);
+            }
+        });
+    }
+
+    /// For `<input type="file">` elements, call `cb` with the selected files when the
+    /// selection changes.
+    pub fn on_file_select(self, cb: impl FnMut(Vec<File>) + 'static) -> Self {
+        self.ref_on_file_select(cb);
+        return self;
+    }
+
+    pub fn ref_on_file_select(&self, mut cb: impl FnMut(Vec<File>) + 'static) -> &Self {
+        return self.ref_on("change", move |ev| {
+            let Some(input) = ev.target().and_then(|t| t.dyn_into::<HtmlInputElement>().ok()) else {
+                return;
+            };
+            cb(file_list(input.files()));
+        });
+    }
+}
+
+/// Read the full contents of a file.
+#[cfg(feature = "futures")]
+pub async fn read_file_bytes(file: &File) -> Result<Vec<u8>, JsValue> {
+    let buffer = JsFuture::from(file.array_buffer()).await?;
+    return Ok(Uint8Array::new(&buffer).to_vec());
+}
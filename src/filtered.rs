@@ -0,0 +1,156 @@
+use std::cmp::Ordering;
+use crate::{
+    Container,
+    ContainerEntry,
+    El,
+};
+
+type Filter<T> = Box<dyn Fn(&T) -> bool>;
+type Sort<T> = Box<dyn Fn(&T, &T) -> Ordering>;
+
+/// A `Container` where only entries matching a filter are shown, optionally kept
+/// sorted (ex: for a searchable list).  Entries that don't match are hidden with
+/// the `hidden` attribute but stay in the container along with anything their
+/// elements own, and are shown again when the filter changes.  Css that sets
+/// `display` on entries needs to exclude `[hidden]` for this to work.
+///
+/// Indexes refer to the container's order, including hidden entries.  Make changes
+/// with the methods here or with `modify` so the filter and sort are reapplied, and
+/// call `refresh` if entries change in a way that affects the filter or sort.
+pub struct FilteredView<T: ContainerEntry> {
+    container: Container<T>,
+    filter: Option<Filter<T>>,
+    sort: Option<Sort<T>>,
+}
+
+impl<T: ContainerEntry> FilteredView<T> {
+    pub fn new(el: El) -> Self {
+        return FilteredView {
+            container: Container::new(el),
+            filter: None,
+            sort: None,
+        };
+    }
+
+    /// The underlying container, for reading entries and subscribing to changes
+    /// (`Container::on_change`).
+    pub fn container(&self) -> &Container<T> {
+        return &self.container;
+    }
+
+    /// Change the underlying container with `f`, then reapply the filter and sort.
+    pub fn modify<R>(&mut self, f: impl FnOnce(&mut Container<T>) -> R) -> R {
+        let out = f(&mut self.container);
+        self.refresh();
+        return out;
+    }
+
+    pub fn len(&self) -> usize {
+        return self.container.len();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.container.is_empty();
+    }
+
+    /// All entries, including hidden ones.
+    pub fn iter(&self) -> core::slice::Iter<'_, T> {
+        return self.container.iter();
+    }
+
+    pub fn get(&self, i: usize) -> Option<&T> {
+        return self.container.get(i);
+    }
+
+    /// See the warning on `Container::iter_mut`.  Call `refresh` after changes that
+    /// affect the filter or sort.
+    pub fn get_mut(&mut self, i: usize) -> Option<&mut T> {
+        return self.container.get_mut(i);
+    }
+
+    pub fn push(&mut self, entry: T) {
+        self.modify(|c| c.push(entry));
+    }
+
+    pub fn extend(&mut self, entries: Vec<T>) {
+        self.modify(|c| c.extend(entries));
+    }
+
+    pub fn insert(&mut self, i: usize, entry: T) {
+        self.modify(|c| c.insert(i, entry));
+    }
+
+    pub fn remove(&mut self, i: usize) -> T {
+        return self.container.remove(i);
+    }
+
+    pub fn clear(&mut self) {
+        self.container.clear();
+    }
+
+    /// Only show entries for which `f` returns true.
+    pub fn set_filter(&mut self, f: impl Fn(&T) -> bool + 'static) {
+        self.filter = Some(Box::new(f));
+        self.refresh();
+    }
+
+    /// Show all entries.
+    pub fn clear_filter(&mut self) {
+        self.filter = None;
+        self.refresh();
+    }
+
+    /// Keep entries in the order defined by `f` (a stable sort).
+    pub fn set_sort_by(&mut self, f: impl Fn(&T, &T) -> Ordering + 'static) {
+        self.sort = Some(Box::new(f));
+        self.refresh();
+    }
+
+    /// Stop sorting.  Entries keep their current order.
+    pub fn clear_sort(&mut self) {
+        self.sort = None;
+    }
+
+    /// Whether the entry matches the filter.
+    fn shown(&self, entry: &T) -> bool {
+        return self.filter.as_ref().is_none_or(|f| f(entry));
+    }
+
+    /// The indexes of shown entries.
+    pub fn visible(&self) -> Vec<usize> {
+        return (0 .. self.container.len()).filter(|i| self.shown(&self.container[*i])).collect();
+    }
+
+    /// Reapply the sort and filter, moving elements in the DOM only where the order
+    /// differs.
+    pub fn refresh(&mut self) {
+        if let Some(sort) = &self.sort {
+            let mut sorted = (0 .. self.container.len()).collect::<Vec<_>>();
+            sorted.sort_by(|a, b| sort(&self.container[*a], &self.container[*b]));
+
+            // The original index of the entry at each current position
+            let mut current = (0 .. self.container.len()).collect::<Vec<_>>();
+            for (at, want) in sorted.into_iter().enumerate() {
+                let from = current.iter().position(|i| *i == want).unwrap();
+                if from != at {
+                    self.container.move_entry(from, at);
+                    let i = current.remove(from);
+                    current.insert(at, i);
+                }
+            }
+        }
+        for entry in &self.container {
+            if self.shown(entry) {
+                entry.el().ref_remove_attr("hidden");
+            } else {
+                entry.el().ref_attr("hidden", "");
+            }
+        }
+    }
+}
+
+impl<T: ContainerEntry> ContainerEntry for FilteredView<T> {
+    fn el(&self) -> &El {
+        return self.container.el();
+    }
+}
@@ -0,0 +1,104 @@
+use gloo_events::EventListener;
+use gloo_utils::document;
+use web_sys::Element;
+use crate::El;
+#[cfg(feature = "futures")]
+use {
+    futures::Future,
+    js_sys::{
+        Function,
+        Promise,
+        Reflect,
+    },
+    wasm_bindgen::{
+        JsCast,
+        JsValue,
+    },
+    wasm_bindgen_futures::JsFuture,
+};
+
+/// Keeps an element fullscreen.  When dropped, fullscreen is exited if the element
+/// is still the fullscreen element.
+pub struct FullscreenGuard {
+    el: Element,
+}
+
+impl Drop for FullscreenGuard {
+    fn drop(&mut self) {
+        if document().fullscreen_element().as_ref() == Some(&self.el) {
+            document().exit_fullscreen();
+        }
+    }
+}
+
+/// Keeps the pointer locked to an element.  When dropped, the pointer is unlocked if
+/// it's still locked to the element.
+pub struct PointerLockGuard {
+    el: Element,
+}
+
+impl Drop for PointerLockGuard {
+    fn drop(&mut self) {
+        if document().pointer_lock_element().as_ref() == Some(&self.el) {
+            document().exit_pointer_lock();
+        }
+    }
+}
+
+impl El {
+    /// Make the element fullscreen. This must be called from a user gesture event
+    /// handler.  Fullscreen lasts until the returned guard is dropped (or the user
+    /// exits it).
+    #[cfg(feature = "futures")]
+    pub fn ref_request_fullscreen(&self) -> impl Future<Output = Result<FullscreenGuard, JsValue>> {
+        let el = self.raw();
+        let res = Reflect::get(&el, &JsValue::from_str("requestFullscreen"))
+            .and_then(|f| f.dyn_into::<Function>())
+            .and_then(|f| f.call0(&el));
+        return async move {
+            let res = res?;
+            if let Some(promise) = res.dyn_ref::<Promise>() {
+                JsFuture::from(promise.clone()).await?;
+            }
+            return Ok(FullscreenGuard { el });
+        };
+    }
+
+    /// Call `cb` when the element enters or leaves fullscreen, with whether it's now
+    /// the fullscreen element.
+    pub fn on_fullscreen_change(self, cb: impl FnMut(bool) + 'static) -> Self {
+        self.ref_on_fullscreen_change(cb);
+        return self;
+    }
+
+    pub fn ref_on_fullscreen_change(&self, mut cb: impl FnMut(bool) + 'static) -> &Self {
+        let el = self.raw();
+        return self.ref_own(|_| EventListener::new(&document(), "fullscreenchange", move |_| {
+            cb(document().fullscreen_element().as_ref() == Some(&el));
+        }));
+    }
+
+    /// Lock the pointer to the element. This must be called from a user gesture
+    /// event handler.  The lock lasts until the returned guard is dropped (or the
+    /// user exits it).  Use `on_pointer_lock_change` to find out when the lock takes
+    /// effect.
+    pub fn ref_request_pointer_lock(&self) -> PointerLockGuard {
+        let el = self.raw();
+        el.request_pointer_lock();
+        return PointerLockGuard { el };
+    }
+
+    /// Call `cb` when the pointer is locked to or unlocked from the element, with
+    /// whether it's now locked to the element.
+    pub fn on_pointer_lock_change(self, cb: impl FnMut(bool) + 'static) -> Self {
+        self.ref_on_pointer_lock_change(cb);
+        return self;
+    }
+
+    pub fn ref_on_pointer_lock_change(&self, mut cb: impl FnMut(bool) + 'static) -> &Self {
+        let el = self.raw();
+        return self.ref_own(|_| EventListener::new(&document(), "pointerlockchange", move |_| {
+            cb(document().pointer_lock_element().as_ref() == Some(&el));
+        }));
+    }
+}
@@ -0,0 +1,137 @@
+use std::{
+    cell::RefCell,
+    rc::Rc,
+};
+use gloo_utils::window;
+use js_sys::Reflect;
+use wasm_bindgen::{
+    prelude::Closure,
+    JsCast,
+    JsValue,
+};
+use web_sys::PositionOptions;
+use crate::{
+    defer,
+    ScopeValue,
+};
+
+/// Options for `watch_position`, see `PositionOptions` on MDN.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct WatchPositionOptions {
+    pub high_accuracy: bool,
+    /// Accept a cached position up to this old.
+    pub maximum_age_ms: Option<u32>,
+    /// Report `GeolocationError::Timeout` if a position takes longer than this.
+    pub timeout_ms: Option<u32>,
+}
+
+/// A position reported by `watch_position`.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct GeoPosition {
+    pub latitude: f64,
+    pub longitude: f64,
+    /// Meters
+    pub accuracy: f64,
+    /// Meters
+    pub altitude: Option<f64>,
+    /// Degrees clockwise from north
+    pub heading: Option<f64>,
+    /// Meters per second
+    pub speed: Option<f64>,
+    /// Milliseconds since the epoch
+    pub timestamp: f64,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum GeolocationError {
+    PermissionDenied,
+    PositionUnavailable,
+    Timeout,
+    /// The browser doesn't support geolocation, or some other failure.
+    Other(String),
+}
+
+fn get_f64(o: &JsValue, key: &str) -> Option<f64> {
+    return Reflect::get(o, &JsValue::from_str(key)).ok()?.as_f64();
+}
+
+fn parse_position(p: &JsValue) -> GeoPosition {
+    let coords = Reflect::get(p, &JsValue::from_str("coords")).unwrap_or(JsValue::UNDEFINED);
+    return GeoPosition {
+        latitude: get_f64(&coords, "latitude").unwrap_or(f64::NAN),
+        longitude: get_f64(&coords, "longitude").unwrap_or(f64::NAN),
+        accuracy: get_f64(&coords, "accuracy").unwrap_or(f64::NAN),
+        altitude: get_f64(&coords, "altitude"),
+        heading: get_f64(&coords, "heading"),
+        speed: get_f64(&coords, "speed"),
+        timestamp: get_f64(p, "timestamp").unwrap_or(f64::NAN),
+    };
+}
+
+fn parse_error(e: &JsValue) -> GeolocationError {
+    match get_f64(e, "code").map(|c| c as u32) {
+        Some(1) => return GeolocationError::PermissionDenied,
+        Some(2) => return GeolocationError::PositionUnavailable,
+        Some(3) => return GeolocationError::Timeout,
+        _ => return GeolocationError::Other(
+            Reflect::get(e, &JsValue::from_str("message"))
+                .ok()
+                .and_then(|m| m.as_string())
+                .unwrap_or_else(|| format!("{:?}", e)),
+        ),
+    }
+}
+
+/// Call `cb` with the device's position each time it changes
+/// (`navigator.geolocation.watchPosition`), until the returned value is dropped.
+/// If geolocation isn't available `cb` is called once with an error.
+pub fn watch_position(
+    options: WatchPositionOptions,
+    cb: impl FnMut(Result<GeoPosition, GeolocationError>) + 'static,
+) -> ScopeValue {
+    let geolocation = match window().navigator().geolocation() {
+        Ok(g) => g,
+        Err(e) => {
+            let mut cb = cb;
+            cb(Err(GeolocationError::Other(format!("Geolocation unavailable: {:?}", e))));
+            return defer(|| { });
+        },
+    };
+    let opts = PositionOptions::new();
+    opts.set_enable_high_accuracy(options.high_accuracy);
+    if let Some(ms) = options.maximum_age_ms {
+        opts.set_maximum_age(ms);
+    }
+    if let Some(ms) = options.timeout_ms {
+        opts.set_timeout(ms);
+    }
+    let cb = Rc::new(RefCell::new(cb));
+    let js_ok = Closure::wrap(Box::new({
+        let cb = cb.clone();
+        move |p: JsValue| {
+            (cb.borrow_mut())(Ok(parse_position(&p)));
+        }
+    }) as Box<dyn FnMut(JsValue)>);
+    let js_err = Closure::wrap(Box::new({
+        let cb = cb.clone();
+        move |e: JsValue| {
+            (cb.borrow_mut())(Err(parse_error(&e)));
+        }
+    }) as Box<dyn FnMut(JsValue)>);
+    let id =
+        match geolocation.watch_position_with_error_callback_and_options(
+            js_ok.as_ref().unchecked_ref(),
+            Some(js_err.as_ref().unchecked_ref()),
+            &opts,
+        ) {
+            Ok(id) => id,
+            Err(e) => {
+                (cb.borrow_mut())(Err(parse_error(&e)));
+                return defer(|| { });
+            },
+        };
+    return defer(move || {
+        geolocation.clear_watch(id);
+        drop((js_ok, js_err));
+    });
+}
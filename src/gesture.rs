@@ -0,0 +1,393 @@
+use std::{
+    cell::{
+        Cell,
+        RefCell,
+    },
+    collections::HashMap,
+    rc::{
+        Rc,
+        Weak,
+    },
+};
+use gloo_events::EventListener;
+use wasm_bindgen::JsCast;
+use web_sys::{
+    Element,
+    MouseEvent,
+    PointerEvent,
+};
+use crate::{
+    El,
+    own::ScopeValue,
+    schedule::timeout,
+};
+
+/// How far (in pixels) a pointer can move during a long press before it's no
+/// longer considered a press.
+const LONGPRESS_SLOP: f64 = 10.;
+
+/// Drag gesture events, see `El::on_drag_gesture`.  Positions are client
+/// coordinates, and `dx`/`dy` are the total movement since the drag started.
+#[derive(Clone, Copy, Debug)]
+pub enum DragGesture {
+    Start {
+        x: f64,
+        y: f64,
+    },
+    Move {
+        x: f64,
+        y: f64,
+        dx: f64,
+        dy: f64,
+    },
+    End {
+        x: f64,
+        y: f64,
+        dx: f64,
+        dy: f64,
+    },
+    /// The browser canceled the pointer (ex: it started scrolling instead).
+    Cancel,
+}
+
+/// Pinch gesture events, see `El::on_pinch`.  `scale` is the distance between the
+/// two pointers relative to the distance when the pinch started, and the center is
+/// the midpoint between the pointers in client coordinates.
+#[derive(Clone, Copy, Debug)]
+pub enum PinchGesture {
+    Start {
+        center_x: f64,
+        center_y: f64,
+    },
+    Move {
+        scale: f64,
+        center_x: f64,
+        center_y: f64,
+    },
+    End,
+}
+
+type PointerHandler<S> = Rc<dyn Fn(&Rc<RefCell<S>>, &PointerEvent)>;
+
+/// Listeners that only exist while a gesture is in progress.
+struct GestureListeners {
+    _move: EventListener,
+    _up: EventListener,
+    _cancel: EventListener,
+}
+
+fn gesture_listeners<
+    S: 'static,
+>(
+    target: &Element,
+    state: &Rc<RefCell<S>>,
+    on_move: impl Fn(&Rc<RefCell<S>>, &PointerEvent) + 'static,
+    on_up: impl Fn(&Rc<RefCell<S>>, &PointerEvent, bool) + 'static,
+) -> GestureListeners {
+    let on_up = Rc::new(on_up);
+    let listener = |event: &'static str, f: PointerHandler<S>| {
+        let state = Rc::downgrade(state);
+        return EventListener::new(target, event, move |ev| {
+            let Some(state) = state.upgrade() else {
+                return;
+            };
+            f(&state, ev.dyn_ref::<PointerEvent>().unwrap());
+        });
+    };
+    return GestureListeners {
+        _move: listener("pointermove", Rc::new(on_move)),
+        _up: listener("pointerup", {
+            let on_up = on_up.clone();
+            Rc::new(move |s, ev| on_up(s, ev, false))
+        }),
+        _cancel: listener("pointercancel", Rc::new(move |s, ev| on_up(s, ev, true))),
+    };
+}
+
+struct DragState {
+    pointer_id: i32,
+    start_x: f64,
+    start_y: f64,
+    _listeners: GestureListeners,
+}
+
+struct PinchState {
+    pointers: HashMap<i32, (f64, f64)>,
+    start_distance: Option<f64>,
+    listeners: Option<GestureListeners>,
+}
+
+impl PinchState {
+    fn geometry(&self) -> Option<(f64, f64, f64)> {
+        let mut points = self.pointers.values();
+        let (Some(a), Some(b)) = (points.next(), points.next()) else {
+            return None;
+        };
+        return Some(((a.0 - b.0).hypot(a.1 - b.1), (a.0 + b.0) / 2., (a.1 + b.1) / 2.));
+    }
+}
+
+impl El {
+    /// Call `cb` as the element is dragged with the primary pointer.  The pointer is
+    /// captured for the duration of the drag, and the temporary move and up
+    /// listeners are removed when the drag ends or the element is dropped.
+    ///
+    /// For touch input you'll probably want to set `touch-action: none` on the
+    /// element so the browser doesn't take over the drag for scrolling.
+    pub fn on_drag_gesture(self, cb: impl FnMut(DragGesture) + 'static) -> Self {
+        self.ref_on_drag_gesture(cb);
+        return self;
+    }
+
+    pub fn ref_on_drag_gesture(&self, cb: impl FnMut(DragGesture) + 'static) -> &Self {
+        let cb = Rc::new(RefCell::new(cb));
+        let state = Rc::new(RefCell::new(None::<DragState>));
+        let raw = self.raw();
+        let down = EventListener::new(&raw, "pointerdown", {
+            let state = Rc::downgrade(&state);
+            let raw = raw.clone();
+            move |ev| {
+                let ev = ev.dyn_ref::<PointerEvent>().unwrap();
+                let Some(state1) = state.upgrade() else {
+                    return;
+                };
+                if !ev.is_primary() || ev.button() != 0 || state1.borrow().is_some() {
+                    return;
+                }
+                _ = raw.set_pointer_capture(ev.pointer_id());
+                let listeners = gesture_listeners(&raw, &state1, {
+                    let cb = cb.clone();
+                    move |state, ev| {
+                        let state = state.borrow();
+                        let Some(s) = state.as_ref().filter(|s| s.pointer_id == ev.pointer_id()) else {
+                            return;
+                        };
+                        let x = ev.client_x() as f64;
+                        let y = ev.client_y() as f64;
+                        let (dx, dy) = (x - s.start_x, y - s.start_y);
+                        drop(state);
+                        (cb.borrow_mut())(DragGesture::Move {
+                            x,
+                            y,
+                            dx,
+                            dy,
+                        });
+                    }
+                }, {
+                    let cb = cb.clone();
+                    let raw = raw.clone();
+                    move |state, ev, canceled| {
+                        let Some(s) = state.borrow_mut().take_if(|s| s.pointer_id == ev.pointer_id()) else {
+                            return;
+                        };
+                        _ = raw.release_pointer_capture(ev.pointer_id());
+                        let x = ev.client_x() as f64;
+                        let y = ev.client_y() as f64;
+                        (cb.borrow_mut())(if canceled {
+                            DragGesture::Cancel
+                        } else {
+                            DragGesture::End {
+                                x,
+                                y,
+                                dx: x - s.start_x,
+                                dy: y - s.start_y,
+                            }
+                        });
+                        drop(s);
+                    }
+                });
+                let x = ev.client_x() as f64;
+                let y = ev.client_y() as f64;
+                *state1.borrow_mut() = Some(DragState {
+                    pointer_id: ev.pointer_id(),
+                    start_x: x,
+                    start_y: y,
+                    _listeners: listeners,
+                });
+                (cb.borrow_mut())(DragGesture::Start {
+                    x,
+                    y,
+                });
+            }
+        });
+        return self.ref_own(|_| (state, down));
+    }
+
+    /// Call `cb` as the element is pinched with two pointers.  The temporary
+    /// listeners are removed when all pointers are released or the element is
+    /// dropped.  See `on_drag_gesture` regarding `touch-action`.
+    pub fn on_pinch(self, cb: impl FnMut(PinchGesture) + 'static) -> Self {
+        self.ref_on_pinch(cb);
+        return self;
+    }
+
+    pub fn ref_on_pinch(&self, cb: impl FnMut(PinchGesture) + 'static) -> &Self {
+        let cb = Rc::new(RefCell::new(cb));
+        let state = Rc::new(RefCell::new(PinchState {
+            pointers: HashMap::new(),
+            start_distance: None,
+            listeners: None,
+        }));
+        let raw = self.raw();
+        let down = EventListener::new(&raw, "pointerdown", {
+            let state: Weak<RefCell<PinchState>> = Rc::downgrade(&state);
+            let raw = raw.clone();
+            move |ev| {
+                let ev = ev.dyn_ref::<PointerEvent>().unwrap();
+                let Some(state1) = state.upgrade() else {
+                    return;
+                };
+                let mut s = state1.borrow_mut();
+                if s.pointers.len() >= 2 {
+                    return;
+                }
+                _ = raw.set_pointer_capture(ev.pointer_id());
+                s.pointers.insert(ev.pointer_id(), (ev.client_x() as f64, ev.client_y() as f64));
+                if s.listeners.is_none() {
+                    s.listeners = Some(gesture_listeners(&raw, &state1, {
+                        let cb = cb.clone();
+                        move |state, ev| {
+                            let mut s = state.borrow_mut();
+                            let Some(p) = s.pointers.get_mut(&ev.pointer_id()) else {
+                                return;
+                            };
+                            *p = (ev.client_x() as f64, ev.client_y() as f64);
+                            let (Some(start), Some((distance, center_x, center_y))) =
+                                (s.start_distance, s.geometry()) else {
+                                    return;
+                                };
+                            drop(s);
+                            (cb.borrow_mut())(PinchGesture::Move {
+                                scale: if start > 0. {
+                                    distance / start
+                                } else {
+                                    1.
+                                },
+                                center_x,
+                                center_y,
+                            });
+                        }
+                    }, {
+                        let cb = cb.clone();
+                        let raw = raw.clone();
+                        move |state, ev, _| {
+                            let mut s = state.borrow_mut();
+                            if s.pointers.remove(&ev.pointer_id()).is_none() {
+                                return;
+                            }
+                            _ = raw.release_pointer_capture(ev.pointer_id());
+                            let ended = s.start_distance.take().is_some();
+                            let listeners = if s.pointers.is_empty() {
+                                s.listeners.take()
+                            } else {
+                                None
+                            };
+                            drop(s);
+                            if ended {
+                                (cb.borrow_mut())(PinchGesture::End);
+                            }
+                            drop(listeners);
+                        }
+                    }));
+                }
+                if let Some((distance, center_x, center_y)) = s.geometry() {
+                    s.start_distance = Some(distance);
+                    drop(s);
+                    (cb.borrow_mut())(PinchGesture::Start {
+                        center_x,
+                        center_y,
+                    });
+                }
+            }
+        });
+        return self.ref_own(|_| (state, down));
+    }
+
+    /// Call `cb` with the client coordinates when the primary pointer is held down
+    /// on the element for `ms` milliseconds without moving much.
+    pub fn on_longpress(self, ms: u32, cb: impl FnMut(f64, f64) + 'static) -> Self {
+        self.ref_on_longpress(ms, cb);
+        return self;
+    }
+
+    pub fn ref_on_longpress(&self, ms: u32, cb: impl FnMut(f64, f64) + 'static) -> &Self {
+        let cb = Rc::new(RefCell::new(cb));
+        let timer = Rc::new(RefCell::new(None::<(f64, f64, ScopeValue)>));
+        let raw = self.raw();
+        let down = EventListener::new(&raw, "pointerdown", {
+            let timer = timer.clone();
+            move |ev| {
+                let ev = ev.dyn_ref::<PointerEvent>().unwrap();
+                if !ev.is_primary() || ev.button() != 0 {
+                    return;
+                }
+                let x = ev.client_x() as f64;
+                let y = ev.client_y() as f64;
+                let fire = timeout(ms, {
+                    let timer = Rc::downgrade(&timer);
+                    let cb = cb.clone();
+                    move || {
+                        let Some(timer) = timer.upgrade() else {
+                            return;
+                        };
+                        let pending = timer.borrow_mut().take();
+                        (cb.borrow_mut())(x, y);
+                        drop(pending);
+                    }
+                });
+                *timer.borrow_mut() = Some((x, y, fire));
+            }
+        });
+        let cancel = |event: &'static str| {
+            let timer = timer.clone();
+            return EventListener::new(&raw, event, move |_| {
+                let pending = timer.borrow_mut().take();
+                drop(pending);
+            });
+        };
+        let up = cancel("pointerup");
+        let pointer_cancel = cancel("pointercancel");
+        let leave = cancel("pointerleave");
+        let move_ = EventListener::new(&raw, "pointermove", {
+            let timer = timer.clone();
+            move |ev| {
+                let ev = ev.dyn_ref::<PointerEvent>().unwrap();
+                let mut timer = timer.borrow_mut();
+                let Some((x, y, _)) = timer.as_ref() else {
+                    return;
+                };
+                if (ev.client_x() as f64 - x).hypot(ev.client_y() as f64 - y) > LONGPRESS_SLOP {
+                    let pending = timer.take();
+                    drop(timer);
+                    drop(pending);
+                }
+            }
+        });
+        return self.ref_own(|_| (timer, down, up, pointer_cancel, leave, move_));
+    }
+
+    /// Call `cb` when the element is clicked twice within `ms` milliseconds. Unlike
+    /// the `dblclick` event the threshold is controlled by you rather than the
+    /// platform.
+    pub fn on_dblclick_within(self, ms: u32, cb: impl FnMut(&MouseEvent) + 'static) -> Self {
+        self.ref_on_dblclick_within(ms, cb);
+        return self;
+    }
+
+    pub fn ref_on_dblclick_within(&self, ms: u32, mut cb: impl FnMut(&MouseEvent) + 'static) -> &Self {
+        let last = Cell::new(None::<f64>);
+        return self.ref_on("click", move |ev| {
+            let ev = ev.dyn_ref::<MouseEvent>().unwrap();
+            let now = ev.time_stamp();
+            match last.get() {
+                Some(prev) if now - prev <= ms as f64 => {
+                    last.set(None);
+                    cb(ev);
+                },
+                _ => {
+                    last.set(Some(now));
+                },
+            }
+        });
+    }
+}
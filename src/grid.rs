@@ -0,0 +1,185 @@
+use std::{
+    cell::RefCell,
+    cmp::Ordering,
+    rc::Rc,
+};
+use crate::{
+    el,
+    Container,
+    ContainerEntry,
+    El,
+};
+
+type ColumnSort<T> = Box<dyn Fn(&T, &T) -> Ordering>;
+type HeaderClick = Rc<RefCell<Option<Box<dyn FnMut(usize)>>>>;
+
+/// A column in a `Grid`: how to build the header cell and the cell for each row.
+pub struct Column<T> {
+    header: Box<dyn Fn() -> El>,
+    cell: Box<dyn Fn(&T) -> El>,
+    sort: Option<ColumnSort<T>>,
+}
+
+impl<T> Column<T> {
+    /// The header and cell builders should return `<th>` and `<td>` elements
+    /// respectively.
+    pub fn new(header: impl Fn() -> El + 'static, cell: impl Fn(&T) -> El + 'static) -> Self {
+        return Column {
+            header: Box::new(header),
+            cell: Box::new(cell),
+            sort: None,
+        };
+    }
+
+    /// Allow sorting rows by this column (see `Grid::sort_by_column`).
+    pub fn sortable(mut self, cmp: impl Fn(&T, &T) -> Ordering + 'static) -> Self {
+        self.sort = Some(Box::new(cmp));
+        return self;
+    }
+}
+
+struct GridRow<T> {
+    value: T,
+    el: El,
+}
+
+impl<T> ContainerEntry for GridRow<T> {
+    fn el(&self) -> &El {
+        return &self.el;
+    }
+}
+
+/// A `<table>` built from column definitions, with a row per value.  Rows are
+/// managed like a `Container`.
+pub struct Grid<T> {
+    el: El,
+    header_cells: Vec<El>,
+    columns: Vec<Column<T>>,
+    rows: Container<GridRow<T>>,
+    header_click: HeaderClick,
+}
+
+impl<T: 'static> Grid<T> {
+    pub fn new(columns: Vec<Column<T>>) -> Self {
+        let header_click = HeaderClick::default();
+        let header_cells = columns.iter().enumerate().map(|(i, c)| {
+            let th = (c.header)();
+            if c.sort.is_some() {
+                th.ref_attr("aria-sort", "none");
+                let header_click = header_click.clone();
+                th.ref_on("click", move |_| {
+                    if let Some(cb) = header_click.borrow_mut().as_mut() {
+                        cb(i);
+                    }
+                });
+            }
+            return th;
+        }).collect::<Vec<_>>();
+        let tbody = el("tbody");
+        return Grid {
+            el: el("table").push(el("thead").push(el("tr").extend(header_cells.clone()))).push(tbody.clone()),
+            header_cells,
+            columns,
+            rows: Container::new(tbody),
+            header_click,
+        };
+    }
+
+    fn build_row(&self, value: T) -> GridRow<T> {
+        let el = el("tr").extend(self.columns.iter().map(|c| (c.cell)(&value)).collect());
+        return GridRow {
+            value,
+            el,
+        };
+    }
+
+    pub fn push(&mut self, value: T) {
+        let row = self.build_row(value);
+        self.rows.push(row);
+    }
+
+    pub fn extend(&mut self, values: impl IntoIterator<Item = T>) {
+        let rows = values.into_iter().map(|v| self.build_row(v)).collect();
+        self.rows.extend(rows);
+    }
+
+    pub fn insert(&mut self, i: usize, value: T) {
+        let row = self.build_row(value);
+        self.rows.insert(i, row);
+    }
+
+    pub fn remove(&mut self, i: usize) -> T {
+        return self.rows.remove(i).value;
+    }
+
+    pub fn clear(&mut self) {
+        self.rows.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        return self.rows.len();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.rows.is_empty();
+    }
+
+    pub fn get(&self, i: usize) -> Option<&T> {
+        return self.rows.get(i).map(|r| &r.value);
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        return self.rows.iter().map(|r| &r.value);
+    }
+
+    /// Modify a row's value and rebuild its cells.
+    pub fn update(&mut self, i: usize, f: impl FnOnce(&mut T)) {
+        let mut value = self.rows.remove(i).value;
+        f(&mut value);
+        self.insert(i, value);
+    }
+
+    /// Sort the rows using the column's comparison (see `Column::sortable`), and
+    /// mark the column header with `aria-sort`.  Does nothing if the column isn't
+    /// sortable.
+    pub fn sort_by_column(&mut self, column: usize, ascending: bool) {
+        let Some(cmp) = self.columns.get(column).and_then(|c| c.sort.as_ref()) else {
+            return;
+        };
+        let mut rows = self.rows.splice(0, self.rows.len(), vec![]).collect::<Vec<_>>();
+        rows.sort_by(|a, b| {
+            let o = cmp(&a.value, &b.value);
+            if ascending {
+                return o;
+            } else {
+                return o.reverse();
+            }
+        });
+        self.rows.extend(rows);
+        for (i, (th, c)) in self.header_cells.iter().zip(&self.columns).enumerate() {
+            if c.sort.is_none() {
+                continue;
+            }
+            th.ref_attr("aria-sort", if i != column {
+                "none"
+            } else if ascending {
+                "ascending"
+            } else {
+                "descending"
+            });
+        }
+    }
+
+    /// Call `cb` with the column index when a sortable column's header is clicked,
+    /// for instance to call `sort_by_column`.  The grid owns the callback, so refer
+    /// to the grid weakly from it to avoid a reference cycle.
+    pub fn on_header_click(&mut self, cb: impl FnMut(usize) + 'static) {
+        *self.header_click.borrow_mut() = Some(Box::new(cb));
+    }
+}
+
+impl<T> ContainerEntry for Grid<T> {
+    fn el(&self) -> &El {
+        return &self.el;
+    }
+}
@@ -0,0 +1,150 @@
+use crate::{
+    ContainerEntry,
+    El,
+};
+
+type GroupKey<K, T> = Box<dyn Fn(&T) -> K>;
+
+/// Like `Container`, but a header element is shown before each run of consecutive
+/// entries with the same group key (ex: a date header in a chat log, or a letter in
+/// a contact list).  Headers are added and removed automatically as entries are
+/// inserted and removed.
+///
+/// Entries are expected to be kept in group order by the caller; if two separate
+/// runs have the same key each gets a header.  Call `refresh_key` after modifying
+/// an entry in a way that changes its group.
+pub struct GroupedContainer<K: PartialEq, T: ContainerEntry> {
+    el: El,
+    key: GroupKey<K, T>,
+    header: Box<dyn Fn(&K) -> El>,
+    entries: Vec<T>,
+    keys: Vec<K>,
+    /// The header before each entry, if it starts a group.
+    headers: Vec<Option<El>>,
+}
+
+impl<K: PartialEq, T: ContainerEntry> GroupedContainer<K, T> {
+    /// `key` produces the group key of an entry and `header` builds the header
+    /// element for a group.
+    pub fn new(el: El, key: impl Fn(&T) -> K + 'static, header: impl Fn(&K) -> El + 'static) -> Self {
+        return GroupedContainer {
+            el,
+            key: Box::new(key),
+            header: Box::new(header),
+            entries: vec![],
+            keys: vec![],
+            headers: vec![],
+        };
+    }
+
+    pub fn len(&self) -> usize {
+        return self.entries.len();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.entries.is_empty();
+    }
+
+    pub fn iter(&self) -> core::slice::Iter<'_, T> {
+        return self.entries.iter();
+    }
+
+    pub fn get(&self, i: usize) -> Option<&T> {
+        return self.entries.get(i);
+    }
+
+    /// See the warning on `Container::iter_mut`.  Call `refresh_key` if the change
+    /// affects the entry's group.
+    pub fn get_mut(&mut self, i: usize) -> Option<&mut T> {
+        return self.entries.get_mut(i);
+    }
+
+    /// The header element shown before the entry at `i`, if it starts a group.
+    pub fn header_el(&self, i: usize) -> Option<&El> {
+        return self.headers.get(i)?.as_ref();
+    }
+
+    /// The child index in the element for entry `i` (or its header, if it has one).
+    fn dom_index(&self, i: usize) -> usize {
+        return i + self.headers[.. i].iter().filter(|h| h.is_some()).count();
+    }
+
+    /// Add or remove the header before entry `i` depending on whether it starts a
+    /// group.
+    fn fix_header(&mut self, i: usize) {
+        if i >= self.entries.len() {
+            return;
+        }
+        let needed = i == 0 || self.keys[i - 1] != self.keys[i];
+        match (needed, self.headers[i].is_some()) {
+            (true, false) => {
+                let header = (self.header)(&self.keys[i]);
+                self.el.ref_splice(self.dom_index(i), 0, vec![header.clone()]);
+                self.headers[i] = Some(header);
+            },
+            (false, true) => {
+                self.el.ref_splice(self.dom_index(i), 1, vec![]);
+                self.headers[i] = None;
+            },
+            _ => { },
+        }
+    }
+
+    pub fn push(&mut self, entry: T) {
+        self.insert(self.entries.len(), entry);
+    }
+
+    pub fn insert(&mut self, i: usize, entry: T) {
+        let key = (self.key)(&entry);
+        self.el.ref_splice(self.dom_index(i), 0, vec![entry.el().clone()]);
+        self.entries.insert(i, entry);
+        self.keys.insert(i, key);
+        self.headers.insert(i, None);
+        self.fix_header(i);
+        self.fix_header(i + 1);
+    }
+
+    pub fn remove(&mut self, i: usize) -> T {
+        let start = self.dom_index(i);
+        let count = if self.headers[i].is_some() {
+            2
+        } else {
+            1
+        };
+        self.el.ref_splice(start, count, vec![]);
+        self.keys.remove(i);
+        self.headers.remove(i);
+        let entry = self.entries.remove(i);
+        self.fix_header(i);
+        return entry;
+    }
+
+    pub fn clear(&mut self) {
+        self.el.ref_clear();
+        self.entries.clear();
+        self.keys.clear();
+        self.headers.clear();
+    }
+
+    /// Recompute the group key of the entry at `i`, updating headers around it.
+    pub fn refresh_key(&mut self, i: usize) {
+        let key = (self.key)(&self.entries[i]);
+        if key == self.keys[i] {
+            return;
+        }
+        self.keys[i] = key;
+        if self.headers[i].is_some() {
+            // Rebuild the header for the new key
+            self.el.ref_splice(self.dom_index(i), 1, vec![]);
+            self.headers[i] = None;
+        }
+        self.fix_header(i);
+        self.fix_header(i + 1);
+    }
+}
+
+impl<K: PartialEq, T: ContainerEntry> ContainerEntry for GroupedContainer<K, T> {
+    fn el(&self) -> &El {
+        return &self.el;
+    }
+}
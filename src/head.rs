@@ -0,0 +1,204 @@
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+};
+use gloo_utils::document;
+use wasm_bindgen::{
+    JsCast,
+    JsValue,
+};
+use web_sys::Element;
+use crate::{
+    defer,
+    error::{
+        report_error,
+        ReportExt,
+    },
+    scope_any,
+    ScopeValue,
+};
+
+struct Titles {
+    /// The title from before any owned titles
+    original: Option<String>,
+    next_id: usize,
+    /// Owned titles by id, latest last
+    titles: Vec<(usize, String)>,
+}
+
+#[derive(Default)]
+struct Favicons {
+    /// The icon links from before any owned favicons
+    original: Vec<Element>,
+    next_id: usize,
+    /// Owned icon links by id, latest last.  Only the latest is in the head.
+    icons: Vec<(usize, Element)>,
+}
+
+thread_local!{
+    static FAVICONS: RefCell<Favicons> = RefCell::new(Favicons::default());
+    static TITLES: RefCell<Titles> = const {
+        RefCell::new(Titles {
+            original: None,
+            next_id: 0,
+            titles: vec![],
+        })
+    };
+    // Number of live guards per body class
+    static BODY_CLASSES: RefCell<HashMap<String, usize>> = RefCell::new(HashMap::new());
+}
+
+/// Set the document title until the returned value is dropped.  When dropped, the
+/// title reverts to the most recent remaining owned title, or the title from before
+/// the first owned title.
+pub fn owned_title(title: &str) -> ScopeValue {
+    let id = TITLES.with(|t| {
+        let mut t = t.borrow_mut();
+        if t.titles.is_empty() {
+            t.original = Some(document().title());
+        }
+        let id = t.next_id;
+        t.next_id += 1;
+        t.titles.push((id, title.to_string()));
+        return id;
+    });
+    document().set_title(title);
+    return defer(move || {
+        let title = TITLES.with(|t| {
+            let mut t = t.borrow_mut();
+            t.titles.retain(|(i, _)| *i != id);
+            match t.titles.last() {
+                Some((_, title)) => return Some(title.clone()),
+                None => return t.original.take(),
+            }
+        });
+        if let Some(title) = title {
+            document().set_title(&title);
+        }
+    });
+}
+
+/// Add an element to the document head, removed when the returned value is dropped.
+fn owned_head_el(tag: &str, attrs: &[(&str, &str)]) -> ScopeValue {
+    let el = (|| {
+        let head = document().head().ok_or_else(|| JsValue::from_str("Document has no head"))?;
+        let el = document().create_element(tag)?;
+        for (k, v) in attrs {
+            el.set_attribute(k, v)?;
+        }
+        head.append_child(&el)?;
+        return Ok::<Element, JsValue>(el);
+    })().or_report();
+    return defer(move || {
+        if let Some(el) = el {
+            el.remove();
+        }
+    });
+}
+
+/// Add `<meta name="..." content="...">` to the head until the returned value is
+/// dropped.
+pub fn owned_meta(name: &str, content: &str) -> ScopeValue {
+    return owned_head_el("meta", &[("name", name), ("content", content)]);
+}
+
+/// Add `<link rel="..." href="...">` to the head until the returned value is dropped.
+pub fn owned_link(rel: &str, href: &str) -> ScopeValue {
+    return owned_head_el("link", &[("rel", rel), ("href", href)]);
+}
+
+/// Use `href` as the page icon until the returned value is dropped.  When dropped,
+/// the icon reverts to the most recent remaining owned favicon, or the icon links
+/// from before the first owned favicon, which are taken out of the head meanwhile.
+pub fn owned_favicon(href: &str) -> ScopeValue {
+    let Some(head) = document().head() else {
+        report_error(JsValue::from_str("Document has no head"));
+        return scope_any(());
+    };
+    let Some(link) = (|| {
+        let link = document().create_element("link")?;
+        link.set_attribute("rel", "icon")?;
+        link.set_attribute("href", href)?;
+        return Ok::<Element, JsValue>(link);
+    })().or_report() else {
+        return scope_any(());
+    };
+    let id = FAVICONS.with(|f| {
+        let mut f = f.borrow_mut();
+        if let Some((_, shown)) = f.icons.last() {
+            shown.remove();
+        } else if let Some(found) = head.query_selector_all("link[rel~=\"icon\"]").or_report() {
+            f.original = (0 .. found.length()).filter_map(|i| found.get(i)).filter_map(|n| n.dyn_into().ok()).collect();
+            for original in &f.original {
+                original.remove();
+            }
+        }
+        let id = f.next_id;
+        f.next_id += 1;
+        f.icons.push((id, link.clone()));
+        return id;
+    });
+    head.append_child(&link).or_report();
+    return defer(move || {
+        let show = FAVICONS.with(|f| {
+            let mut f = f.borrow_mut();
+            let Some(i) = f.icons.iter().position(|(i, _)| *i == id) else {
+                return vec![];
+            };
+            let (_, link) = f.icons.remove(i);
+
+            // Only the latest is shown, so earlier guards can go without changes
+            if i < f.icons.len() {
+                return vec![];
+            }
+            link.remove();
+            match f.icons.last() {
+                Some((_, link)) => return vec![link.clone()],
+                None => return std::mem::take(&mut f.original),
+            }
+        });
+        let Some(head) = document().head() else {
+            return;
+        };
+        for link in show {
+            head.append_child(&link).or_report();
+        }
+    });
+}
+
+/// Add a class to the body until the returned value is dropped (ex: `no-scroll`
+/// while a dialog is open).  Guards for the same class are counted, so the class is
+/// only removed when the last one is dropped.
+pub fn owned_body_class(class: &str) -> ScopeValue {
+    let first = BODY_CLASSES.with(|c| {
+        let mut c = c.borrow_mut();
+        let count = c.entry(class.to_string()).or_insert(0);
+        *count += 1;
+        return *count == 1;
+    });
+    if first {
+        if let Some(body) = document().body() {
+            body.class_list().add_1(class).or_report();
+        }
+    }
+    let class = class.to_string();
+    return defer(move || {
+        let last = BODY_CLASSES.with(|c| {
+            let mut c = c.borrow_mut();
+            let Some(count) = c.get_mut(&class) else {
+                return false;
+            };
+            *count -= 1;
+            if *count > 0 {
+                return false;
+            }
+            c.remove(&class);
+            return true;
+        });
+        if last {
+            if let Some(body) = document().body() {
+                body.class_list().remove_1(&class).or_report();
+            }
+        }
+    });
+}
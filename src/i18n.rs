@@ -0,0 +1,59 @@
+use std::cell::RefCell;
+use gloo_utils::document;
+use crate::{
+    error::ReportExt,
+    El,
+    Listeners,
+    ScopeValue,
+};
+
+thread_local!{
+    static LOCALE: RefCell<String> = const {
+        RefCell::new(String::new())
+    };
+    static LOCALE_LISTENERS: Listeners<String> = Listeners::new();
+}
+
+/// The active locale, as set by `set_locale`.  Empty if never set.
+pub fn locale() -> String {
+    return LOCALE.with(|l| l.borrow().clone());
+}
+
+/// Change the active locale (ex: `en-US`), setting the document's `lang` attribute
+/// and re-rendering all text bindings.
+pub fn set_locale(locale: &str) {
+    LOCALE.with(|l| *l.borrow_mut() = locale.to_string());
+    if let Some(root) = document().document_element() {
+        root.set_attribute("lang", locale).or_report();
+    }
+    let locale = locale.to_string();
+    LOCALE_LISTENERS.with(|l| l.clone()).emit(&locale);
+}
+
+/// Call `cb` with the new locale whenever it changes, until the returned value is
+/// dropped.
+pub fn subscribe_locale(cb: impl FnMut(&String) + 'static) -> ScopeValue {
+    return LOCALE_LISTENERS.with(|l| l.listen(cb));
+}
+
+impl El {
+    /// Set the element's text from the active locale now and whenever the locale
+    /// changes, for as long as the element lives.
+    pub fn text_binding(self, render: impl Fn(&str) -> String + 'static) -> Self {
+        self.ref_text_binding(render);
+        return self;
+    }
+
+    pub fn ref_text_binding(&self, render: impl Fn(&str) -> String + 'static) -> &Self {
+        self.ref_text(&render(&locale()));
+        return self.ref_own(|e| {
+            let e = e.weak();
+            return subscribe_locale(move |locale| {
+                let Some(e) = e.upgrade() else {
+                    return;
+                };
+                e.ref_text(&render(locale));
+            });
+        });
+    }
+}
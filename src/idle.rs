@@ -0,0 +1,100 @@
+use std::{
+    cell::RefCell,
+    rc::{
+        Rc,
+        Weak,
+    },
+};
+use gloo_events::{
+    EventListener,
+    EventListenerOptions,
+    EventListenerPhase,
+};
+use gloo_utils::window;
+use js_sys::Date;
+use crate::{
+    schedule::timeout,
+    scope_any,
+    ScopeValue,
+};
+
+const ACTIVITY_EVENTS: &[&str] = &["mousemove", "mousedown", "keydown", "touchstart", "wheel", "scroll"];
+
+struct UserIdle {
+    timeout_ms: u32,
+    last_activity: f64,
+    idle: bool,
+    timer: Option<ScopeValue>,
+}
+
+/// Schedule an idle check for `ms` from now.
+fn arm(
+    state: &Weak<RefCell<UserIdle>>,
+    on_idle: &Rc<RefCell<dyn FnMut()>>,
+    ms: u32,
+) -> ScopeValue {
+    let state = state.clone();
+    let on_idle = on_idle.clone();
+    return timeout(ms, move || {
+        let Some(state1) = state.upgrade() else {
+            return;
+        };
+        let mut s = state1.borrow_mut();
+        let elapsed = (Date::now() - s.last_activity).max(0.) as u32;
+        if elapsed < s.timeout_ms {
+            // There was activity since the timer was set, check again later
+            s.timer = Some(arm(&state, &on_idle, s.timeout_ms - elapsed));
+            return;
+        }
+        s.idle = true;
+        s.timer = None;
+        drop(s);
+        (on_idle.borrow_mut())();
+    });
+}
+
+/// Call `on_idle` when there's been no user input (mouse, keyboard, touch,
+/// scrolling) for `timeout_ms`, then `on_active` when input resumes.  Input is
+/// only timestamped as it happens, so frequent events like `mousemove` are cheap.
+/// The listeners are removed when the returned value is dropped.
+pub fn on_user_idle(
+    timeout_ms: u32,
+    on_idle: impl FnMut() + 'static,
+    on_active: impl FnMut() + 'static,
+) -> ScopeValue {
+    let on_idle: Rc<RefCell<dyn FnMut()>> = Rc::new(RefCell::new(on_idle));
+    let on_active = Rc::new(RefCell::new(on_active));
+    let state = Rc::new(RefCell::new(UserIdle {
+        timeout_ms,
+        last_activity: Date::now(),
+        idle: false,
+        timer: None,
+    }));
+    state.borrow_mut().timer = Some(arm(&Rc::downgrade(&state), &on_idle, timeout_ms));
+    let mut listeners = vec![];
+    for event in ACTIVITY_EVENTS.iter().copied() {
+        listeners.push(EventListener::new_with_options(&window(), event, EventListenerOptions {
+            phase: EventListenerPhase::Capture,
+            passive: true,
+        }, {
+            let state = Rc::downgrade(&state);
+            let on_idle = on_idle.clone();
+            let on_active = on_active.clone();
+            move |_| {
+                let Some(state1) = state.upgrade() else {
+                    return;
+                };
+                let mut s = state1.borrow_mut();
+                s.last_activity = Date::now();
+                if !s.idle {
+                    return;
+                }
+                s.idle = false;
+                s.timer = Some(arm(&state, &on_idle, s.timeout_ms));
+                drop(s);
+                (on_active.borrow_mut())();
+            }
+        }));
+    }
+    return scope_any((listeners, state));
+}
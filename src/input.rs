@@ -0,0 +1,110 @@
+use std::{
+    cell::{
+        Cell,
+        RefCell,
+    },
+    rc::Rc,
+};
+use wasm_bindgen::JsCast;
+use web_sys::{
+    CompositionEvent,
+    InputEvent,
+};
+use crate::El;
+
+/// Text input events, as delivered by `InputState`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TextInput {
+    /// An IME composition started - the text is provisional until committed.
+    CompositionStart,
+    /// The provisional composition text changed.
+    CompositionUpdate(String),
+    /// Input was committed, either regular typing or a finished composition. This is
+    /// the point at which to read and process the element's value.
+    Commit,
+}
+
+/// Tracks IME composition state (ex: for CJK text entry) for an input element,
+/// distinguishing provisional composition input from committed input.  Raw `input`
+/// events fire for each composition step, so processing them directly can mangle
+/// text being composed.
+///
+/// The listeners are attached to the element and removed when it's dropped. The
+/// `InputState` can be dropped or kept to query the composition state.
+#[derive(Clone)]
+pub struct InputState {
+    composing: Rc<Cell<bool>>,
+}
+
+impl InputState {
+    pub fn new(el: &El, cb: impl FnMut(TextInput) + 'static) -> InputState {
+        let composing = Rc::new(Cell::new(false));
+        let cb = Rc::new(RefCell::new(cb));
+        el.ref_on_composition_start({
+            let composing = composing.clone();
+            let cb = cb.clone();
+            move |_| {
+                composing.set(true);
+                (cb.borrow_mut())(TextInput::CompositionStart);
+            }
+        });
+        el.ref_on_composition_update({
+            let cb = cb.clone();
+            move |ev| {
+                (cb.borrow_mut())(TextInput::CompositionUpdate(ev.data().unwrap_or_default()));
+            }
+        });
+        el.ref_on_composition_end({
+            let composing = composing.clone();
+            let cb = cb.clone();
+            move |_| {
+                composing.set(false);
+                (cb.borrow_mut())(TextInput::Commit);
+            }
+        });
+        el.ref_on("input", {
+            let composing = composing.clone();
+            move |ev| {
+                if composing.get() || ev.dyn_ref::<InputEvent>().map(|ev| ev.is_composing()).unwrap_or(false) {
+                    return;
+                }
+                (cb.borrow_mut())(TextInput::Commit);
+            }
+        });
+        return InputState { composing };
+    }
+
+    /// Whether an IME composition is in progress.
+    pub fn is_composing(&self) -> bool {
+        return self.composing.get();
+    }
+}
+
+impl El {
+    pub fn on_composition_start(self, cb: impl FnMut(&CompositionEvent) + 'static) -> Self {
+        self.ref_on_composition_start(cb);
+        return self;
+    }
+
+    pub fn ref_on_composition_start(&self, mut cb: impl FnMut(&CompositionEvent) + 'static) -> &Self {
+        return self.ref_on("compositionstart", move |ev| cb(ev.dyn_ref().unwrap()));
+    }
+
+    pub fn on_composition_update(self, cb: impl FnMut(&CompositionEvent) + 'static) -> Self {
+        self.ref_on_composition_update(cb);
+        return self;
+    }
+
+    pub fn ref_on_composition_update(&self, mut cb: impl FnMut(&CompositionEvent) + 'static) -> &Self {
+        return self.ref_on("compositionupdate", move |ev| cb(ev.dyn_ref().unwrap()));
+    }
+
+    pub fn on_composition_end(self, cb: impl FnMut(&CompositionEvent) + 'static) -> Self {
+        self.ref_on_composition_end(cb);
+        return self;
+    }
+
+    pub fn ref_on_composition_end(&self, mut cb: impl FnMut(&CompositionEvent) + 'static) -> &Self {
+        return self.ref_on("compositionend", move |ev| cb(ev.dyn_ref().unwrap()));
+    }
+}
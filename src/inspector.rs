@@ -0,0 +1,155 @@
+//! A development overlay for inspecting rooting elements, with the `inspector`
+//! feature.
+use std::{
+    cell::RefCell,
+    rc::Rc,
+};
+use gloo_events::{
+    EventListener,
+    EventListenerOptions,
+};
+use gloo_utils::document;
+use wasm_bindgen::{
+    JsCast,
+    JsValue,
+};
+use web_sys::{
+    Element,
+    KeyboardEvent,
+    MouseEvent,
+    Node,
+};
+use crate::{
+    el,
+    mounted_roots,
+    scope_any,
+    style,
+    El,
+    Position,
+    Root,
+    ScopeValue,
+};
+
+struct Overlay {
+    highlight: El,
+    panel: El,
+    _root: Root,
+}
+
+/// The deepest rooting element containing the node.
+fn find_el(target: &Node) -> Option<El> {
+    for root in mounted_roots() {
+        if !root.raw().contains(Some(target)) {
+            continue;
+        }
+        let mut at = root;
+        loop {
+            let Some(next) = at.children().into_iter().find(|c| c.raw().contains(Some(target))) else {
+                return Some(at);
+            };
+            at = next;
+        }
+    }
+    return None;
+}
+
+fn new_overlay() -> Overlay {
+    let highlight =
+        el("div").style(
+            style()
+                .position(Position::Fixed)
+                .z_index(2147483646)
+                .prop("pointer-events", "none")
+                .prop("outline", "2px solid #e0409a")
+                .prop("background", "rgba(224, 64, 154, 0.15)")
+                .prop("display", "none"),
+        );
+    let panel =
+        el("div").style(
+            style()
+                .position(Position::Fixed)
+                .z_index(2147483647)
+                .prop("pointer-events", "none")
+                .prop("background", "#222")
+                .prop("color", "#eee")
+                .prop("font", "12px monospace")
+                .prop("padding", "4px 6px")
+                .prop("white-space", "pre")
+                .prop("display", "none"),
+        );
+    let body = document().body().map(Element::from).ok_or_else(|| JsValue::from_str("Document has no body"));
+    return Overlay {
+        _root: Root::append_at(body, vec![highlight.clone(), panel.clone()]),
+        highlight,
+        panel,
+    };
+}
+
+fn inspect(overlay: &Overlay, target: Option<El>) {
+    let Some(target) = target else {
+        overlay.highlight.ref_style(style().prop("display", "none"));
+        overlay.panel.ref_style(style().prop("display", "none"));
+        return;
+    };
+    let rect = target.ref_bounds();
+    overlay
+        .highlight
+        .ref_style(
+            style()
+                .prop("display", "block")
+                .left_px(rect.x)
+                .top_px(rect.y)
+                .width_px(rect.width)
+                .height_px(rect.height),
+        );
+    let mut text = format!("<{}>", target.raw().tag_name().to_ascii_lowercase());
+    if let Some(name) = target.ref_get_debug_name() {
+        text.push_str(&format!(" {:?}", name));
+    }
+    text.push_str(&format!("\nowned: {}\nchildren: {}", target.owned_count(), target.children().len()));
+    overlay.panel.ref_text(&text);
+    overlay.panel.ref_style(style().prop("display", "block").left_px(rect.x).top_px(rect.bottom() + 4.));
+}
+
+/// Install the inspector: pressing Ctrl+Alt+I toggles it, and while it's on
+/// hovering the page highlights the rooting element under the pointer and shows its
+/// debug name (see `El::ref_debug_name`), number of owned values and number of
+/// children.  The inspector is removed when the returned value is dropped.
+pub fn enable_inspector() -> ScopeValue {
+    let overlay = Rc::new(RefCell::new(None::<Overlay>));
+    let toggle = EventListener::new(&document(), "keydown", {
+        let overlay = overlay.clone();
+        move |ev| {
+            let Some(ev) = ev.dyn_ref::<KeyboardEvent>() else {
+                return;
+            };
+            if !(ev.ctrl_key() && ev.alt_key() && ev.code() == "KeyI") {
+                return;
+            }
+            let mut overlay = overlay.borrow_mut();
+            if overlay.is_some() {
+                *overlay = None;
+            } else {
+                *overlay = Some(new_overlay());
+            }
+        }
+    });
+    let hover =
+        EventListener::new_with_options(
+            &document(),
+            "mousemove",
+            EventListenerOptions::run_in_capture_phase(),
+            move |ev| {
+                let overlay = overlay.borrow();
+                let Some(overlay) = overlay.as_ref() else {
+                    return;
+                };
+                let Some(ev) = ev.dyn_ref::<MouseEvent>() else {
+                    return;
+                };
+                let target = ev.target().and_then(|t| t.dyn_into::<Node>().ok());
+                inspect(overlay, target.and_then(|t| find_el(&t)));
+            },
+        );
+    return scope_any((toggle, hover));
+}
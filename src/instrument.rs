@@ -0,0 +1,60 @@
+//! Instrumentation of crate operations.  Element child changes (push, extend,
+//! splice, move, clear) and multi-step `Container` operations are:
+//!
+//! * With the `perf` feature, measured with User Timing, showing up in the browser
+//!   devtools performance panel named like `rooting splice <ul>`.
+//!
+//! * With the `log` feature, logged at trace level along with element drops and
+//!   owned values being added, labeled with the element's debug name if set (see
+//!   `El::ref_debug_name`).
+use crate::El;
+
+/// Describe an element for logs, like `<ul #1234 "sidebar">`.
+#[cfg(feature = "log")]
+pub(crate) fn describe(tag: &str, ptr_id: Option<usize>, debug_name: Option<&str>) -> String {
+    let mut out = format!("<{}", tag.to_ascii_lowercase());
+    if let Some(ptr_id) = ptr_id {
+        out.push_str(&format!(" #{}", ptr_id));
+    }
+    if let Some(name) = debug_name {
+        out.push_str(&format!(" {:?}", name));
+    }
+    out.push('>');
+    return out;
+}
+
+/// Log an operation on the element at trace level, with the `log` feature.
+#[cfg(feature = "log")]
+pub(crate) fn trace(op: &str, el: &El) {
+    if !log::log_enabled!(log::Level::Trace) {
+        return;
+    }
+    let desc = describe(&el.raw().tag_name(), Some(el.ptr_id()), el.ref_get_debug_name().as_deref());
+    log::trace!("rooting {} {}", op, desc);
+}
+
+#[cfg(not(feature = "log"))]
+#[inline(always)]
+pub(crate) fn trace(_op: &str, _el: &El) { }
+
+#[cfg(feature = "perf")]
+pub(crate) fn measure<T>(op: &str, el: &El, f: impl FnOnce() -> T) -> T {
+    trace(op, el);
+    let Some(performance) = gloo_utils::window().performance() else {
+        return f();
+    };
+    let name = format!("rooting {} <{}>", op, el.raw().tag_name().to_ascii_lowercase());
+    let start = format!("{} start", name);
+    _ = performance.mark(&start);
+    let out = f();
+    _ = performance.measure_with_start_mark(&name, &start);
+    performance.clear_marks_with_mark_name(&start);
+    return out;
+}
+
+#[cfg(not(feature = "perf"))]
+#[inline(always)]
+pub(crate) fn measure<T>(op: &str, el: &El, f: impl FnOnce() -> T) -> T {
+    trace(op, el);
+    return f();
+}
@@ -0,0 +1,118 @@
+use std::{
+    cell::Cell,
+    rc::{
+        Weak,
+        Rc,
+    },
+};
+use js_sys::Array;
+use wasm_bindgen::{
+    prelude::Closure,
+    JsValue,
+    JsCast,
+};
+use web_sys::{
+    Element,
+    IntersectionObserver as IntersectionObserver1,
+    IntersectionObserverEntry,
+    IntersectionObserverInit,
+};
+use crate::{
+    el,
+    error::report_error,
+    scope_any,
+    El,
+    ScopeValue,
+};
+
+pub struct IntersectionObserver_ {
+    pub js_intersection_observer: IntersectionObserver1,
+    _js_cb: ScopeValue,
+}
+
+/// This is a convenience wrapper around `web_sys` `IntersectionObserver`, like
+/// `ResizeObserver`.  A single observer can monitor multiple elements.
+#[derive(Clone)]
+pub struct IntersectionObserver(pub Rc<IntersectionObserver_>);
+
+/// See `IntersectionObserver`'s `observe` method.
+pub struct IntersectionObserveHandle {
+    target: Element,
+    intersection_observer: Weak<IntersectionObserver_>,
+}
+
+impl IntersectionObserver {
+    /// The callback receives an array of `IntersectionObserverEntry` for all
+    /// observed elements whose intersection changed.
+    pub fn new(cb: impl Fn(Array) + 'static) -> Self {
+        return Self::new_with_options(cb, &IntersectionObserverInit::new());
+    }
+
+    pub fn new_with_options(cb: impl Fn(Array) + 'static, opts: &IntersectionObserverInit) -> Self {
+        let js_cb = Closure::wrap(Box::new(move |entries: Array, _| -> () {
+            cb(entries);
+        }) as Box<dyn Fn(Array, JsValue)>);
+        let intersection_observer =
+            IntersectionObserver1::new_with_options(js_cb.as_ref().unchecked_ref(), opts).unwrap();
+        return Self(Rc::new(IntersectionObserver_ {
+            js_intersection_observer: intersection_observer,
+            _js_cb: scope_any(js_cb),
+        }));
+    }
+
+    /// Add the target element to the observation set.  The callback is invoked
+    /// shortly after with the initial intersection state.
+    ///
+    /// When the `IntersectionObserveHandle` is dropped, the target will stop being
+    /// observed.
+    pub fn observe(&self, target: &Element) -> IntersectionObserveHandle {
+        self.0.js_intersection_observer.observe(target);
+        return IntersectionObserveHandle {
+            target: target.clone(),
+            intersection_observer: Rc::downgrade(&self.0),
+        };
+    }
+}
+
+impl Drop for IntersectionObserveHandle {
+    fn drop(&mut self) {
+        let Some(intersection_observer) = self.intersection_observer.upgrade() else {
+            return;
+        };
+        intersection_observer.js_intersection_observer.unobserve(&self.target);
+    }
+}
+
+/// Create a placeholder element that's replaced with the built element (see
+/// `El::ref_replace`) the first time the placeholder scrolls into view.  Use this
+/// to defer building heavy below-the-fold content.
+///
+/// The placeholder must be a child of another `El` to be replaced.  If it isn't
+/// when it comes into view the error goes to the error handler (see
+/// `set_error_handler`) and the build is retried the next time it comes into view.
+pub fn el_lazy(builder: impl FnOnce() -> El + 'static) -> El {
+    let builder = Cell::new(Some(builder));
+    return el("div").own(move |e| {
+        let intersection_observer = IntersectionObserver::new({
+            let e = e.weak();
+            move |entries| {
+                if !entries.iter().any(|x| x.unchecked_into::<IntersectionObserverEntry>().is_intersecting()) {
+                    return;
+                }
+                let Some(e) = e.upgrade() else {
+                    return;
+                };
+                if !e.has_parent() {
+                    report_error(JsValue::from_str("Lazy element placeholder has no parent El to be replaced in"));
+                    return;
+                }
+                let Some(builder) = builder.take() else {
+                    return;
+                };
+                e.ref_replace(vec![builder()]);
+            }
+        });
+        let handle = intersection_observer.observe(&e.raw());
+        return (intersection_observer, handle);
+    });
+}
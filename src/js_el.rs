@@ -0,0 +1,126 @@
+//! Handles for using rooting elements from javascript.  Each handle holds a
+//! reference to the element - the element lives until every handle (Rust and
+//! javascript) is released.  Javascript releases a handle by calling `free()`, or
+//! automatically when the handle is garbage collected if the bindings are built
+//! with weak references enabled (`wasm-bindgen --weak-refs`, using
+//! `FinalizationRegistry`).
+use wasm_bindgen::{
+    prelude::wasm_bindgen,
+    JsValue,
+};
+use web_sys::Element;
+use crate::{
+    el_from_raw,
+    root::Root,
+    try_el,
+    El,
+};
+
+/// A javascript handle to an `El`.
+#[wasm_bindgen(js_name = RootingEl)]
+pub struct JsEl(El);
+
+#[wasm_bindgen(js_class = RootingEl)]
+impl JsEl {
+    /// Create a new element with the tag.
+    #[wasm_bindgen(constructor)]
+    pub fn new(tag: &str) -> Result<JsEl, JsValue> {
+        return Ok(JsEl(try_el(tag)?));
+    }
+
+    /// Wrap an existing DOM element.
+    #[wasm_bindgen(js_name = fromRaw)]
+    pub fn from_raw(element: Element) -> JsEl {
+        return JsEl(el_from_raw(element));
+    }
+
+    /// Get another handle to the same element.
+    #[wasm_bindgen(js_name = clone)]
+    pub fn js_clone(&self) -> JsEl {
+        return JsEl(self.0.clone());
+    }
+
+    /// The DOM element.
+    pub fn raw(&self) -> Element {
+        return self.0.raw();
+    }
+
+    /// Append a child.  The element keeps the child alive, independent of the
+    /// child's handle.
+    pub fn push(&self, child: &JsEl) {
+        self.0.ref_push(child.0.clone());
+    }
+
+    /// Remove `remove` children at `offset` and insert `add` in their place.  Throws
+    /// if the range is outside of the children.
+    pub fn splice(&self, offset: usize, remove: usize, add: Vec<JsEl>) -> Result<(), JsValue> {
+        self.0.ref_try_splice(offset, remove, add.into_iter().map(|e| e.0).collect())?;
+        return Ok(());
+    }
+
+    /// Remove all children.
+    pub fn clear(&self) {
+        self.0.ref_clear();
+    }
+
+    pub fn text(&self, text: &str) {
+        self.0.ref_text(text);
+    }
+
+    pub fn attr(&self, key: &str, value: &str) {
+        self.0.ref_attr(key, value);
+    }
+
+    #[wasm_bindgen(js_name = removeAttr)]
+    pub fn remove_attr(&self, key: &str) {
+        self.0.ref_remove_attr(key);
+    }
+
+    pub fn classes(&self, keys: Vec<String>) {
+        self.0.ref_classes(&keys.iter().map(|k| k.as_str()).collect::<Vec<_>>());
+    }
+
+    #[wasm_bindgen(js_name = removeClasses)]
+    pub fn remove_classes(&self, keys: Vec<String>) {
+        self.0.ref_remove_classes(&keys.iter().map(|k| k.as_str()).collect::<Vec<_>>());
+    }
+
+    /// Append the element to `parent`'s children.  The element stays mounted until
+    /// the returned root is freed.
+    #[wasm_bindgen(js_name = mountIn)]
+    pub fn mount_in(&self, parent: Element) -> JsRoot {
+        return JsRoot(Root::append_at(parent, vec![self.0.clone()]));
+    }
+}
+
+impl JsEl {
+    /// Get the Rust element for this handle.
+    pub fn el(&self) -> &El {
+        return &self.0;
+    }
+}
+
+impl From<El> for JsEl {
+    fn from(value: El) -> Self {
+        return JsEl(value);
+    }
+}
+
+impl From<JsEl> for El {
+    fn from(value: JsEl) -> Self {
+        return value.0;
+    }
+}
+
+/// A javascript handle to a mounted element, see `RootingEl.mountIn`.  Freeing it
+/// removes the element from the document.
+#[wasm_bindgen(js_name = RootingRoot)]
+pub struct JsRoot(Root);
+
+#[wasm_bindgen(js_class = RootingRoot)]
+impl JsRoot {
+    /// Handles to the mounted elements.
+    pub fn elements(&self) -> Vec<JsEl> {
+        return self.0.elements().iter().map(|e| JsEl(e.clone())).collect();
+    }
+}
@@ -0,0 +1,100 @@
+use std::{
+    cell::RefCell,
+    rc::Rc,
+};
+use wasm_bindgen::JsValue;
+use web_sys::Element;
+use crate::{
+    attach::watch_connected,
+    El,
+    ScopeValue,
+};
+
+type Mount = Box<dyn FnOnce(&Element) -> JsValue>;
+type Unmount = Box<dyn FnOnce(&Element, JsValue)>;
+
+struct JsMount {
+    el: Element,
+    mount: Option<Mount>,
+    unmount: Option<Unmount>,
+    handle: Option<JsValue>,
+    watch: Option<ScopeValue>,
+}
+
+impl JsMount {
+    fn mount(&mut self) {
+        let Some(mount) = self.mount.take() else {
+            return;
+        };
+        self.handle = Some(mount(&self.el));
+    }
+}
+
+struct JsMountGuard(Rc<RefCell<JsMount>>);
+
+impl Drop for JsMountGuard {
+    fn drop(&mut self) {
+        let mut s = self.0.borrow_mut();
+        let watch = s.watch.take();
+        let (Some(unmount), Some(handle)) = (s.unmount.take(), s.handle.take()) else {
+            return;
+        };
+        let el = s.el.clone();
+        drop(s);
+        drop(watch);
+        unmount(&el, handle);
+    }
+}
+
+impl El {
+    /// Host a javascript component (ex: a chart or map widget) in this element.
+    /// `mount` is called with the element once it's in the document (immediately if
+    /// it already is) and returns a handle for the component.  `unmount` is called
+    /// with the handle when this element is dropped, if `mount` was called.
+    pub fn mount_js(
+        self,
+        mount: impl FnOnce(&Element) -> JsValue + 'static,
+        unmount: impl FnOnce(&Element, JsValue) + 'static,
+    ) -> Self {
+        self.ref_mount_js(mount, unmount);
+        return self;
+    }
+
+    pub fn ref_mount_js(
+        &self,
+        mount: impl FnOnce(&Element) -> JsValue + 'static,
+        unmount: impl FnOnce(&Element, JsValue) + 'static,
+    ) -> &Self {
+        let raw = self.raw();
+        let state = Rc::new(RefCell::new(JsMount {
+            el: raw.clone(),
+            mount: Some(Box::new(mount)),
+            unmount: Some(Box::new(unmount)),
+            handle: None,
+            watch: None,
+        }));
+        if raw.is_connected() {
+            state.borrow_mut().mount();
+        } else {
+            let watch = watch_connected(&raw, {
+                let state = Rc::downgrade(&state);
+                move |connected| {
+                    if !connected {
+                        return;
+                    }
+                    let Some(state) = state.upgrade() else {
+                        return;
+                    };
+                    let watch = {
+                        let mut s = state.borrow_mut();
+                        s.mount();
+                        s.watch.take()
+                    };
+                    drop(watch);
+                }
+            });
+            state.borrow_mut().watch = Some(watch);
+        }
+        return self.ref_own(|_| JsMountGuard(state));
+    }
+}
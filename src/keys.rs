@@ -0,0 +1,214 @@
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    fmt::Display,
+    rc::Rc,
+    str::FromStr,
+};
+use gloo_events::EventListener;
+use gloo_utils::window;
+use wasm_bindgen::JsCast;
+use web_sys::{
+    EventTarget,
+    HtmlElement,
+    KeyboardEvent,
+};
+use crate::El;
+
+/// A key combination, like `Ctrl+K`.  Parse one from a string with `+`-separated
+/// modifiers followed by the key: `Ctrl`, `Alt`, `Shift`, `Meta`, and `Mod` (`Meta`
+/// on Apple platforms, `Ctrl` elsewhere). The key is a `KeyboardEvent.key` value
+/// (ex: `k`, `ArrowUp`, `Enter`), case-insensitive.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct Chord {
+    pub ctrl: bool,
+    pub alt: bool,
+    pub shift: bool,
+    pub meta: bool,
+    /// Lowercase key.
+    pub key: String,
+}
+
+fn is_apple() -> bool {
+    let platform = window().navigator().platform().unwrap_or_default();
+    return platform.starts_with("Mac") || platform.starts_with("iP");
+}
+
+impl FromStr for Chord {
+    type Err = ShortcutError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut out = Chord {
+            ctrl: false,
+            alt: false,
+            shift: false,
+            meta: false,
+            key: String::new(),
+        };
+        let mut parts = s.split('+').collect::<Vec<_>>();
+        let Some(key) = parts.pop().filter(|k| !k.is_empty()) else {
+            return Err(ShortcutError::Parse(s.to_string()));
+        };
+        for modifier in parts {
+            match modifier.to_ascii_lowercase().as_str() {
+                "ctrl" | "control" => out.ctrl = true,
+                "alt" | "option" => out.alt = true,
+                "shift" => out.shift = true,
+                "meta" | "cmd" | "super" => out.meta = true,
+                "mod" => if is_apple() {
+                    out.meta = true;
+                } else {
+                    out.ctrl = true;
+                },
+                _ => return Err(ShortcutError::Parse(s.to_string())),
+            }
+        }
+        out.key = key.to_lowercase();
+        return Ok(out);
+    }
+}
+
+impl Chord {
+    fn from_event(ev: &KeyboardEvent, key: String) -> Chord {
+        return Chord {
+            ctrl: ev.ctrl_key(),
+            alt: ev.alt_key(),
+            shift: ev.shift_key(),
+            meta: ev.meta_key(),
+            key,
+        };
+    }
+}
+
+/// The key a physical key code (ex: `KeyK`, `Digit1`) would produce on a US
+/// layout.  Used to match shortcuts when the active layout produces a non-latin
+/// character or a modifier changes the produced character (ex: `Alt` on Apple
+/// platforms).
+fn key_from_code(code: &str) -> Option<String> {
+    if let Some(k) = code.strip_prefix("Key") {
+        return Some(k.to_lowercase());
+    }
+    if let Some(k) = code.strip_prefix("Digit") {
+        return Some(k.to_string());
+    }
+    return None;
+}
+
+#[derive(Debug)]
+pub enum ShortcutError {
+    /// The shortcut string couldn't be parsed.
+    Parse(String),
+    /// The shortcut is already bound.
+    Conflict(String),
+}
+
+impl Display for ShortcutError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ShortcutError::Parse(s) => return write!(f, "Invalid shortcut [{}]", s),
+            ShortcutError::Conflict(s) => return write!(f, "Shortcut [{}] is already bound", s),
+        }
+    }
+}
+
+impl std::error::Error for ShortcutError { }
+
+type Binding = Rc<RefCell<dyn FnMut(&KeyboardEvent)>>;
+type Bindings = Rc<RefCell<HashMap<Chord, Binding>>>;
+
+fn is_editable(target: Option<EventTarget>) -> bool {
+    let Some(target) = target.and_then(|t| t.dyn_into::<HtmlElement>().ok()) else {
+        return false;
+    };
+    if target.is_content_editable() {
+        return true;
+    }
+    let tag = target.tag_name();
+    return tag == "INPUT" || tag == "TEXTAREA" || tag == "SELECT";
+}
+
+/// A set of keyboard shortcuts.  All shortcuts are unbound when this is dropped, so
+/// attach it to an element with `.own()` to scope the shortcuts to the element's
+/// lifetime.
+///
+/// Shortcuts without `Ctrl`, `Alt`, or `Meta` are ignored while typing in text
+/// inputs and editable elements.
+pub struct Shortcuts {
+    bindings: Bindings,
+    _listener: EventListener,
+}
+
+impl Shortcuts {
+    /// Shortcuts that apply anywhere in the window.
+    pub fn global() -> Shortcuts {
+        return Shortcuts::new_on(&window());
+    }
+
+    /// Shortcuts that apply when focus is within the element.
+    pub fn on_el(el: &El) -> Shortcuts {
+        return Shortcuts::new_on(&el.raw());
+    }
+
+    fn new_on(target: &EventTarget) -> Shortcuts {
+        let bindings = Bindings::default();
+        let listener = EventListener::new(target, "keydown", {
+            let bindings = bindings.clone();
+            move |ev| {
+                let ev = ev.dyn_ref::<KeyboardEvent>().unwrap();
+                if ev.is_composing() {
+                    return;
+                }
+                let mut cb = None;
+                {
+                    let bindings = bindings.borrow();
+                    let chord = Chord::from_event(ev, ev.key().to_lowercase());
+                    if let Some(found) = bindings.get(&chord) {
+                        cb = Some((chord, found.clone()));
+                    } else if let Some(key) = key_from_code(&ev.code()) {
+                        let chord = Chord::from_event(ev, key);
+                        if let Some(found) = bindings.get(&chord) {
+                            cb = Some((chord, found.clone()));
+                        }
+                    }
+                }
+                let Some((chord, cb)) = cb else {
+                    return;
+                };
+                if !(chord.ctrl || chord.alt || chord.meta) && is_editable(ev.target()) {
+                    return;
+                }
+                ev.prevent_default();
+                (cb.borrow_mut())(ev);
+            }
+        });
+        return Shortcuts {
+            bindings,
+            _listener: listener,
+        };
+    }
+
+    /// Bind a shortcut (see `Chord` for the format).  Returns an error if the
+    /// shortcut is invalid or already bound in this set.
+    pub fn bind(&self, chord: &str, cb: impl FnMut(&KeyboardEvent) + 'static) -> Result<(), ShortcutError> {
+        let parsed = Chord::from_str(chord)?;
+        let mut bindings = self.bindings.borrow_mut();
+        if bindings.contains_key(&parsed) {
+            return Err(ShortcutError::Conflict(chord.to_string()));
+        }
+        bindings.insert(parsed, Rc::new(RefCell::new(cb)));
+        return Ok(());
+    }
+
+    /// Whether a shortcut is bound in this set.  Returns an error if the shortcut is
+    /// invalid.
+    pub fn is_bound(&self, chord: &str) -> Result<bool, ShortcutError> {
+        let parsed = Chord::from_str(chord)?;
+        return Ok(self.bindings.borrow().contains_key(&parsed));
+    }
+
+    /// Remove a shortcut, returning whether it was bound.
+    pub fn unbind(&self, chord: &str) -> Result<bool, ShortcutError> {
+        let parsed = Chord::from_str(chord)?;
+        return Ok(self.bindings.borrow_mut().remove(&parsed).is_some());
+    }
+}
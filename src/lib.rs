@@ -1,15 +1,157 @@
 pub mod own;
+mod instrument;
+pub mod error;
 pub mod resize;
+pub mod intersect;
 pub mod el;
 pub mod container;
+pub mod sectioned;
+pub mod list_selection;
+pub mod filtered;
+pub mod grouped;
+pub mod snapshot;
+pub mod template;
+pub mod builder;
+pub mod pool;
+pub mod grid;
+pub mod tree;
+pub mod stack;
+pub mod view_slot;
+pub mod attach;
+pub mod js_mount;
+pub mod measure;
+pub mod style;
+pub mod css;
+pub mod listeners;
+pub mod theme;
+pub mod head;
+pub mod lifecycle;
+pub mod idle;
+pub mod geolocation;
+pub mod notify;
+pub mod audio;
+pub mod speech;
+pub mod device;
+pub mod print;
+pub mod pan;
+pub mod split;
+pub mod roving;
+pub mod announce;
+pub mod undo;
+pub mod prim;
+pub mod obs;
+#[cfg(feature = "serde")]
+pub mod serde_interop;
+#[cfg(feature = "js")]
+pub mod js_el;
+#[cfg(feature = "atomics")]
+pub mod send;
+#[cfg(feature = "inspector")]
+pub mod inspector;
+#[cfg(feature = "bench")]
+pub mod bench;
+pub mod storage;
+#[cfg(feature = "futures")]
+pub mod wake_lock;
+#[cfg(feature = "i18n")]
+pub mod i18n;
 #[cfg(feature = "futures")]
 pub mod spawn;
+#[cfg(feature = "futures")]
+pub mod paged;
 pub mod root;
+pub mod document;
+pub mod choice;
+pub mod input;
+pub mod selection;
+pub mod file;
+pub mod object_url;
+pub mod media;
+pub mod fullscreen;
+pub mod dialog;
+pub mod popup;
+pub mod gesture;
+pub mod keys;
+pub mod channel;
+#[cfg(feature = "worker")]
+pub mod worker;
+pub mod boundary;
+pub mod memo;
+pub mod schedule;
+pub mod batch;
 
 pub use own::*;
+pub use error::*;
 pub use resize::*;
+pub use intersect::*;
 pub use el::*;
 pub use container::*;
+pub use sectioned::*;
+pub use list_selection::*;
+pub use filtered::*;
+pub use grouped::*;
+pub use snapshot::*;
+pub use template::*;
+pub use builder::*;
+pub use pool::*;
+pub use grid::*;
+pub use tree::*;
+pub use stack::*;
+pub use view_slot::*;
+pub use measure::*;
+pub use style::*;
+pub use css::*;
+pub use listeners::*;
+pub use theme::*;
+pub use head::*;
+pub use lifecycle::*;
+pub use idle::*;
+pub use geolocation::*;
+pub use notify::*;
+pub use audio::*;
+pub use speech::*;
+pub use device::*;
+pub use print::*;
+pub use pan::*;
+pub use split::*;
+pub use roving::*;
+pub use announce::*;
+pub use undo::*;
+pub use prim::*;
+pub use obs::*;
+#[cfg(feature = "serde")]
+pub use serde_interop::*;
+#[cfg(feature = "js")]
+pub use js_el::*;
+#[cfg(feature = "atomics")]
+pub use send::*;
+#[cfg(feature = "inspector")]
+pub use inspector::*;
+pub use storage::local_storage;
+#[cfg(feature = "futures")]
+pub use wake_lock::*;
+#[cfg(feature = "i18n")]
+pub use i18n::*;
 #[cfg(feature = "futures")]
 pub use spawn::*;
+#[cfg(feature = "futures")]
+pub use paged::*;
 pub use root::*;
+pub use document::*;
+pub use choice::*;
+pub use input::*;
+pub use file::*;
+pub use object_url::*;
+pub use media::*;
+pub use fullscreen::*;
+pub use dialog::*;
+pub use popup::*;
+pub use gesture::*;
+pub use keys::*;
+pub use channel::*;
+#[cfg(feature = "worker")]
+pub use worker::*;
+pub use boundary::*;
+pub use memo::*;
+pub use schedule::*;
+pub use batch::*;
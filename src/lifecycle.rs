@@ -0,0 +1,46 @@
+use gloo_events::EventListener;
+use gloo_utils::{
+    document,
+    window,
+};
+use wasm_bindgen::JsValue;
+use crate::{
+    scope_any,
+    ScopeValue,
+};
+
+/// Call `cb` when the page is about to be unloaded (closed, reloaded, navigated
+/// away).  If `cb` returns `true` (ex: there are unsaved changes) the browser asks
+/// the user to confirm leaving.  The listener is removed when the returned value is
+/// dropped.
+pub fn on_before_unload(mut cb: impl FnMut() -> bool + 'static) -> ScopeValue {
+    return scope_any(EventListener::new(&window(), "beforeunload", move |ev| {
+        if cb() {
+            ev.prevent_default();
+
+            // Older browsers require `returnValue` to be set
+            _ = js_sys::Reflect::set(ev, &JsValue::from_str("returnValue"), &JsValue::from_str(""));
+        }
+    }));
+}
+
+/// Ask the user to confirm leaving the page for as long as the returned value is
+/// alive, ex: while an editor has unsaved changes.
+pub fn unsaved_changes_guard() -> ScopeValue {
+    return on_before_unload(|| true);
+}
+
+/// Whether the page is currently visible (the tab is in the foreground and the
+/// window isn't minimized).
+pub fn page_visible() -> bool {
+    return !document().hidden();
+}
+
+/// Call `cb` with whether the page is visible each time the visibility changes (ex:
+/// to pause work while the tab is in the background).  The listener is removed when
+/// the returned value is dropped.
+pub fn on_visibility_change(mut cb: impl FnMut(bool) + 'static) -> ScopeValue {
+    return scope_any(EventListener::new(&document(), "visibilitychange", move |_| {
+        cb(page_visible());
+    }));
+}
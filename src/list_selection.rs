@@ -0,0 +1,245 @@
+use std::{
+    cell::RefCell,
+    rc::{
+        Rc,
+        Weak,
+    },
+};
+use gloo_events::EventListener;
+use wasm_bindgen::JsCast;
+use web_sys::{
+    MouseEvent,
+    Node,
+};
+use crate::{
+    scope_any,
+    Container,
+    ContainerEntry,
+    El,
+    Listeners,
+    ScopeValue,
+    WeakEl,
+};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SelectionMode {
+    Single,
+    /// Ctrl/Cmd-click toggles entries and Shift-click selects a range.
+    Multiple,
+}
+
+struct Selection_ {
+    el: WeakEl,
+    mode: SelectionMode,
+    class: String,
+    selected: Vec<WeakEl>,
+    anchor: Option<WeakEl>,
+    changes: Listeners<Vec<usize>>,
+    own: Vec<ScopeValue>,
+}
+
+/// Tracks which entries of a `Container` are selected, adding a class to the
+/// selected entries' elements.  Clicking an entry selects it.  Selection follows
+/// entries as the container changes, and removed entries are deselected.
+///
+/// Each clone refers to the same selection.  The selection stops tracking clicks
+/// and container changes when the last clone is dropped.
+#[derive(Clone)]
+pub struct Selection(Rc<RefCell<Selection_>>);
+
+impl Selection {
+    pub fn new<T: ContainerEntry>(container: &Container<T>, mode: SelectionMode, selected_class: &str) -> Self {
+        let el = container.el().clone();
+        let state = Rc::new(RefCell::new(Selection_ {
+            el: el.weak(),
+            mode,
+            class: selected_class.to_string(),
+            selected: vec![],
+            anchor: None,
+            changes: Listeners::new(),
+            own: vec![],
+        }));
+        let on_change = container.on_change({
+            let state = Rc::downgrade(&state);
+            move |_| {
+                let Some(state) = state.upgrade() else {
+                    return;
+                };
+                Selection(state).prune();
+            }
+        });
+        let click = EventListener::new(&el.raw(), "click", {
+            let state = Rc::downgrade(&state);
+            move |ev| {
+                let Some(ev) = ev.dyn_ref::<MouseEvent>() else {
+                    return;
+                };
+                Selection::on_click(&state, ev);
+            }
+        });
+        {
+            let mut s = state.borrow_mut();
+            s.own.push(on_change);
+            s.own.push(scope_any(click));
+        }
+        return Selection(state);
+    }
+
+    fn on_click(state: &Weak<RefCell<Selection_>>, ev: &MouseEvent) {
+        let Some(state) = state.upgrade() else {
+            return;
+        };
+        let this = Selection(state);
+        let Some(el) = this.0.borrow().el.upgrade() else {
+            return;
+        };
+        let Some(target) = ev.target().and_then(|t| t.dyn_into::<Node>().ok()) else {
+            return;
+        };
+        let Some(i) = el.children().iter().position(|c| c.raw().contains(Some(&target))) else {
+            return;
+        };
+        let mode = this.0.borrow().mode;
+        if mode == SelectionMode::Multiple && ev.shift_key() {
+            this.select_range_to(i);
+        } else if mode == SelectionMode::Multiple && (ev.ctrl_key() || ev.meta_key()) {
+            this.toggle(i);
+        } else {
+            this.select_only(i);
+        }
+    }
+
+    /// The container element's children, and whether each is selected.
+    fn entries(&self) -> Vec<(El, bool)> {
+        let s = self.0.borrow();
+        let Some(el) = s.el.upgrade() else {
+            return vec![];
+        };
+        return el.children().into_iter().map(|c| {
+            let selected = s.selected.iter().any(|w| w.upgrade().is_some_and(|w| w.ptr_id() == c.ptr_id()));
+            (c, selected)
+        }).collect();
+    }
+
+    /// Set the selected entries, updating classes and notifying listeners if
+    /// anything changed.
+    fn set_selected(&self, selected: impl Fn(usize, bool) -> bool) {
+        let entries = self.entries();
+        let mut changed = false;
+        let mut new_selected = vec![];
+        {
+            let s = self.0.borrow();
+            for (i, (e, was)) in entries.iter().enumerate() {
+                let now = selected(i, *was);
+                if now {
+                    new_selected.push(e.weak());
+                }
+                if now != *was {
+                    changed = true;
+                    if now {
+                        e.ref_classes(&[&s.class]);
+                    } else {
+                        e.ref_remove_classes(&[&s.class]);
+                    }
+                }
+            }
+        }
+        let dropped = {
+            let mut s = self.0.borrow_mut();
+            let dropped = s.selected.len() != new_selected.len();
+            s.selected = new_selected;
+            dropped
+        };
+        if changed || dropped {
+            let changes = self.0.borrow().changes.clone();
+            changes.emit(&self.selected_indices());
+        }
+    }
+
+    /// Drop entries that were removed from the container.
+    fn prune(&self) {
+        self.set_selected(|_, was| was);
+    }
+
+    /// The indexes of selected entries, in order.
+    pub fn selected_indices(&self) -> Vec<usize> {
+        return self.entries().iter().enumerate().filter(|(_, (_, selected))| *selected).map(|(i, _)| i).collect();
+    }
+
+    pub fn is_selected(&self, i: usize) -> bool {
+        return self.entries().get(i).is_some_and(|(_, selected)| *selected);
+    }
+
+    fn set_anchor(&self, i: usize) {
+        let anchor = self.entries().get(i).map(|(e, _)| e.weak());
+        self.0.borrow_mut().anchor = anchor;
+    }
+
+    /// Select only the entry at `i`.
+    pub fn select_only(&self, i: usize) {
+        self.set_selected(|j, _| j == i);
+        self.set_anchor(i);
+    }
+
+    /// Select the entry at `i`, deselecting others in `SelectionMode::Single`.
+    pub fn select(&self, i: usize) {
+        if self.0.borrow().mode == SelectionMode::Single {
+            self.select_only(i);
+            return;
+        }
+        self.set_selected(|j, was| was || j == i);
+        self.set_anchor(i);
+    }
+
+    pub fn deselect(&self, i: usize) {
+        self.set_selected(|j, was| was && j != i);
+    }
+
+    pub fn toggle(&self, i: usize) {
+        if self.is_selected(i) {
+            self.deselect(i);
+        } else {
+            self.select(i);
+        }
+    }
+
+    /// Select the entries from the last selected (or clicked) entry to `i`
+    /// inclusive, deselecting others.  In `SelectionMode::Single` this selects just
+    /// `i`.
+    pub fn select_range_to(&self, i: usize) {
+        let anchor = {
+            let s = self.0.borrow();
+            if s.mode == SelectionMode::Single {
+                None
+            } else {
+                s.anchor.as_ref().and_then(|a| a.upgrade())
+            }
+        };
+        let Some(anchor) = anchor.and_then(|a| self.0.borrow().el.upgrade().and_then(|el| el.ref_index_of(&a))) else {
+            self.select_only(i);
+            return;
+        };
+        let (low, high) = (anchor.min(i), anchor.max(i));
+        self.set_selected(|j, _| j >= low && j <= high);
+    }
+
+    /// Select every entry.  Does nothing in `SelectionMode::Single`.
+    pub fn select_all(&self) {
+        if self.0.borrow().mode == SelectionMode::Single {
+            return;
+        }
+        self.set_selected(|_, _| true);
+    }
+
+    pub fn clear(&self) {
+        self.set_selected(|_, _| false);
+        self.0.borrow_mut().anchor = None;
+    }
+
+    /// Call `cb` with the selected indexes when the set of selected entries changes
+    /// (including when a selected entry is removed from the container), until the
+    /// returned value is dropped.
+    pub fn on_change(&self, cb: impl FnMut(&Vec<usize>) + 'static) -> ScopeValue {
+        return self.0.borrow().changes.listen(cb);
+    }
+}
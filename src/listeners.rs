@@ -0,0 +1,77 @@
+use std::{
+    cell::RefCell,
+    rc::Rc,
+};
+use crate::{
+    defer,
+    ScopeValue,
+};
+
+type Listener<T> = Rc<RefCell<dyn FnMut(&T)>>;
+
+struct Listeners_<T> {
+    next_id: usize,
+    listeners: Vec<(usize, Listener<T>)>,
+}
+
+/// A list of callbacks to notify of changes.  Each subscription lasts as long as the
+/// `ScopeValue` returned by `listen`, so it can be owned by an element.
+///
+/// Listeners can be added or removed during `emit`; the changes apply to the next
+/// `emit`.
+pub struct Listeners<T>(Rc<RefCell<Listeners_<T>>>);
+
+impl<T> Clone for Listeners<T> {
+    fn clone(&self) -> Self {
+        return Listeners(self.0.clone());
+    }
+}
+
+impl<T: 'static> Default for Listeners<T> {
+    fn default() -> Self {
+        return Self::new();
+    }
+}
+
+impl<T: 'static> Listeners<T> {
+    pub fn new() -> Self {
+        return Listeners(Rc::new(RefCell::new(Listeners_ {
+            next_id: 0,
+            listeners: vec![],
+        })));
+    }
+
+    /// Call `cb` on each `emit` until the returned value is dropped.
+    pub fn listen(&self, cb: impl FnMut(&T) + 'static) -> ScopeValue {
+        let id = {
+            let mut l = self.0.borrow_mut();
+            let id = l.next_id;
+            l.next_id += 1;
+            l.listeners.push((id, Rc::new(RefCell::new(cb))));
+            id
+        };
+        let l = Rc::downgrade(&self.0);
+        return defer(move || {
+            let Some(l) = l.upgrade() else {
+                return;
+            };
+            l.borrow_mut().listeners.retain(|(i, _)| *i != id);
+        });
+    }
+
+    /// Call all listeners with the value.
+    pub fn emit(&self, value: &T) {
+        let listeners = self.0.borrow().listeners.iter().map(|(_, l)| l.clone()).collect::<Vec<_>>();
+        for l in listeners {
+            // Skip a listener re-entrantly emitting to itself
+            let Ok(mut l) = l.try_borrow_mut() else {
+                continue;
+            };
+            l(value);
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.0.borrow().listeners.is_empty();
+    }
+}
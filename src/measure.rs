@@ -0,0 +1,83 @@
+use wasm_bindgen::JsCast;
+use web_sys::{
+    DomRect,
+    HtmlElement,
+};
+use crate::El;
+
+/// A rectangle in css pixels, relative to the viewport.
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub struct Rect {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+impl Rect {
+    pub fn right(&self) -> f64 {
+        return self.x + self.width;
+    }
+
+    pub fn bottom(&self) -> f64 {
+        return self.y + self.height;
+    }
+
+    pub fn contains(&self, x: f64, y: f64) -> bool {
+        return x >= self.x && x < self.right() && y >= self.y && y < self.bottom();
+    }
+}
+
+impl From<DomRect> for Rect {
+    fn from(r: DomRect) -> Self {
+        return Rect {
+            x: r.x(),
+            y: r.y(),
+            width: r.width(),
+            height: r.height(),
+        };
+    }
+}
+
+/// A width and height in css pixels.
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub struct Size {
+    pub width: f64,
+    pub height: f64,
+}
+
+impl El {
+    /// The element's border box relative to the viewport (`getBoundingClientRect`).
+    pub fn ref_bounds(&self) -> Rect {
+        return self.raw().get_bounding_client_rect().into();
+    }
+
+    /// The element's layout size including borders (`offsetWidth`/`offsetHeight`).
+    /// This is zero for non-html elements (ex: svg).
+    pub fn ref_offset_size(&self) -> Size {
+        let Some(e) = self.raw().dyn_ref::<HtmlElement>().cloned() else {
+            return Size::default();
+        };
+        return Size {
+            width: e.offset_width() as f64,
+            height: e.offset_height() as f64,
+        };
+    }
+
+    /// The element's inner size including padding but not borders or scrollbars
+    /// (`clientWidth`/`clientHeight`).
+    pub fn ref_client_size(&self) -> Size {
+        let e = self.raw();
+        return Size {
+            width: e.client_width() as f64,
+            height: e.client_height() as f64,
+        };
+    }
+}
+
+/// Get the bounds of all the elements in one pass.  Do this rather than
+/// interleaving measurements with style changes, which forces a layout for each
+/// measurement.  See also `batch_read`.
+pub fn measure(elements: &[El]) -> Vec<Rect> {
+    return elements.iter().map(|e| e.ref_bounds()).collect();
+}
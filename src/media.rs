@@ -0,0 +1,135 @@
+use js_sys::{
+    Function,
+    Reflect,
+};
+use wasm_bindgen::{
+    prelude::Closure,
+    JsCast,
+    JsValue,
+};
+use web_sys::{
+    console,
+    Blob,
+    HtmlMediaElement,
+};
+use crate::{
+    el,
+    error::{
+        report_error,
+        ReportExt,
+    },
+    object_url::owned_object_url,
+    El,
+};
+
+/// The owned slot for the object URL of a `set_src_blob` source.
+const BLOB_SRC: &str = "blob_src";
+
+/// Create a new `<video>` element.
+pub fn video_el() -> El {
+    return el("video");
+}
+
+/// Create a new `<audio>` element.
+pub fn audio_el() -> El {
+    return el("audio");
+}
+
+/// These methods are for `<video>` and `<audio>` elements (ex: from `video_el()`).
+/// Calling them on other elements sends an error to the error handler.
+impl El {
+    fn media(&self) -> Option<HtmlMediaElement> {
+        match self.raw().dyn_into::<HtmlMediaElement>() {
+            Ok(m) => return Some(m),
+            Err(_) => {
+                report_error(JsValue::from_str("Element is not a media element"));
+                return None;
+            },
+        }
+    }
+
+    /// Start playback. If playback is refused (ex: by autoplay policies) a warning is
+    /// logged to the console.
+    pub fn ref_play(&self) -> &Self {
+        let Some(m) = self.media() else {
+            return self;
+        };
+        let Some(promise) = m.play().or_report() else {
+            return self;
+        };
+
+        // Used for both outcomes so the closure is freed either way; playing resolves
+        // with `undefined`
+        let done = Closure::once_into_js(|e: JsValue| {
+            if !e.is_undefined() {
+                console::warn_2(&JsValue::from_str("Media playback was refused:"), &e);
+            }
+        });
+        if let Some(then) =
+            Reflect::get(&promise, &JsValue::from_str("then")).ok().and_then(|t| t.dyn_into::<Function>().ok()) {
+            _ = then.call2(&promise, &done, &done);
+        }
+        return self;
+    }
+
+    pub fn ref_pause(&self) -> &Self {
+        if let Some(m) = self.media() {
+            m.pause().or_report();
+        }
+        return self;
+    }
+
+    pub fn set_src(self, src: &str) -> Self {
+        self.ref_set_src(src);
+        return self;
+    }
+
+    pub fn ref_set_src(&self, src: &str) -> &Self {
+        if let Some(m) = self.media() {
+            m.set_src(src);
+        }
+        self.set_owned_slot(BLOB_SRC, None);
+        return self;
+    }
+
+    /// Play the blob's contents via an object URL. The URL is revoked when the
+    /// source changes or the element is dropped.
+    pub fn set_src_blob(self, blob: &Blob) -> Self {
+        self.ref_set_src_blob(blob);
+        return self;
+    }
+
+    pub fn ref_set_src_blob(&self, blob: &Blob) -> &Self {
+        let Some((url, url_scope)) = owned_object_url(blob).or_report() else {
+            return self;
+        };
+        self.ref_set_src(&url);
+        self.set_owned_slot(BLOB_SRC, Some(url_scope));
+        return self;
+    }
+
+    /// The current playback position in seconds.
+    pub fn current_time(&self) -> f64 {
+        return self.media().map(|m| m.current_time()).unwrap_or(0.);
+    }
+
+    pub fn ref_set_current_time(&self, seconds: f64) -> &Self {
+        if let Some(m) = self.media() {
+            m.set_current_time(seconds);
+        }
+        return self;
+    }
+
+    /// Call `cb` with the playback position in seconds whenever it changes.
+    pub fn on_timeupdate(self, cb: impl FnMut(f64) + 'static) -> Self {
+        self.ref_on_timeupdate(cb);
+        return self;
+    }
+
+    pub fn ref_on_timeupdate(&self, mut cb: impl FnMut(f64) + 'static) -> &Self {
+        let Some(m) = self.media() else {
+            return self;
+        };
+        return self.ref_on("timeupdate", move |_| cb(m.current_time()));
+    }
+}
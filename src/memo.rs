@@ -0,0 +1,43 @@
+use crate::El;
+
+/// Caches a built element along with the key it was built from, so the subtree is
+/// only rebuilt when the key changes.
+///
+/// ```ignore
+/// let mut memo = ElMemo::new();
+/// ...
+/// parent.ref_clear().ref_push(memo.get(state.user_id, |id| build_profile(*id)));
+/// ```
+pub struct ElMemo<K: PartialEq> {
+    last: Option<(K, El)>,
+}
+
+impl<K: PartialEq> ElMemo<K> {
+    pub fn new() -> Self {
+        return Self { last: None };
+    }
+
+    /// Return the previously built element if `key` is equal to the key it was built
+    /// with, otherwise build and cache a new element.
+    pub fn get(&mut self, key: K, builder: impl FnOnce(&K) -> El) -> El {
+        if let Some((last_key, last_el)) = &self.last {
+            if *last_key == key {
+                return last_el.clone();
+            }
+        }
+        let el = builder(&key);
+        self.last = Some((key, el.clone()));
+        return el;
+    }
+
+    /// Forget the cached element, so the next `get` rebuilds.
+    pub fn clear(&mut self) {
+        self.last = None;
+    }
+}
+
+impl<K: PartialEq> Default for ElMemo<K> {
+    fn default() -> Self {
+        return Self::new();
+    }
+}
@@ -0,0 +1,87 @@
+#[cfg(feature = "futures")]
+use std::future::Future;
+use web_sys::{
+    Notification,
+    NotificationOptions,
+    NotificationPermission,
+};
+use crate::{
+    defer,
+    error::ReportExt,
+    ScopeValue,
+};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Permission {
+    /// The user hasn't decided, notifications can't be shown yet.
+    Default,
+    Granted,
+    Denied,
+}
+
+impl From<NotificationPermission> for Permission {
+    fn from(p: NotificationPermission) -> Self {
+        match p {
+            NotificationPermission::Granted => return Permission::Granted,
+            NotificationPermission::Denied => return Permission::Denied,
+            _ => return Permission::Default,
+        }
+    }
+}
+
+/// The current notification permission, without asking the user.
+pub fn permission() -> Permission {
+    return Notification::permission().into();
+}
+
+/// Ask the user for permission to show notifications, if they haven't decided yet.
+/// Browsers generally require this to be called from a user gesture (ex: a click
+/// handler).
+#[cfg(feature = "futures")]
+pub fn request_permission() -> impl Future<Output = Permission> {
+    let promise = Notification::request_permission();
+    return async move {
+        let Ok(promise) = promise else {
+            return permission();
+        };
+        match wasm_bindgen_futures::JsFuture::from(promise).await {
+            Ok(p) => return NotificationPermission::from_js_value(&p).map(Permission::from).unwrap_or_else(permission),
+            Err(_) => return permission(),
+        }
+    };
+}
+
+/// The contents of a notification for `show_notification`.
+#[derive(Clone, Debug, Default)]
+pub struct NotificationOpts {
+    pub title: String,
+    pub body: Option<String>,
+    pub icon: Option<String>,
+    /// Notifications with the same tag replace each other.
+    pub tag: Option<String>,
+    /// Keep the notification open until the user interacts with it or it's dropped.
+    pub require_interaction: bool,
+}
+
+/// Show a system notification, closing it when the returned value is dropped (if
+/// it's still open).  Errors (ex: permission hasn't been granted) go to the error
+/// handler.
+pub fn show_notification(opts: NotificationOpts) -> ScopeValue {
+    let js_opts = NotificationOptions::new();
+    if let Some(body) = &opts.body {
+        js_opts.set_body(body);
+    }
+    if let Some(icon) = &opts.icon {
+        js_opts.set_icon(icon);
+    }
+    if let Some(tag) = &opts.tag {
+        js_opts.set_tag(tag);
+    }
+    js_opts.set_require_interaction(opts.require_interaction);
+    let notification = Notification::new_with_options(&opts.title, &js_opts).or_report();
+    return defer(move || {
+        if let Some(n) = notification {
+            n.close();
+        }
+    });
+}
@@ -0,0 +1,20 @@
+use wasm_bindgen::JsValue;
+use web_sys::{
+    Blob,
+    Url,
+};
+use crate::own::{
+    defer,
+    ScopeValue,
+};
+
+/// Create an object URL for the blob (ex: for an image preview), returning the URL
+/// and a scope value that revokes the URL when dropped.  Attach the scope value to
+/// the element using the URL with `.own()` so the blob can be freed along with the
+/// element.
+pub fn owned_object_url(blob: &Blob) -> Result<(String, ScopeValue), JsValue> {
+    let url = Url::create_object_url_with_blob(blob)?;
+    return Ok((url.clone(), defer(move || {
+        _ = Url::revoke_object_url(&url);
+    })));
+}
@@ -0,0 +1,271 @@
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    hash::Hash,
+    rc::Rc,
+};
+use crate::{
+    Container,
+    ContainerEntry,
+    ContainerMap,
+    El,
+    Listeners,
+    ScopeValue,
+};
+
+/// A change to an `ObsVec`: `remove` values starting at `offset` were replaced by
+/// `add`.
+#[derive(Clone, Debug)]
+pub struct ObsVecSplice<T> {
+    pub offset: usize,
+    pub remove: usize,
+    pub add: Vec<T>,
+}
+
+/// A shared list that notifies listeners of each change as a splice, so views can
+/// apply just that change (see `Container::bind`).  Clones refer to the same list.
+pub struct ObsVec<T> {
+    values: Rc<RefCell<Vec<T>>>,
+    listeners: Listeners<ObsVecSplice<T>>,
+}
+
+impl<T> Clone for ObsVec<T> {
+    fn clone(&self) -> Self {
+        return ObsVec {
+            values: self.values.clone(),
+            listeners: self.listeners.clone(),
+        };
+    }
+}
+
+impl<T: Clone + 'static> Default for ObsVec<T> {
+    fn default() -> Self {
+        return Self::new(vec![]);
+    }
+}
+
+impl<T: Clone + 'static> ObsVec<T> {
+    pub fn new(values: Vec<T>) -> ObsVec<T> {
+        return ObsVec {
+            values: Rc::new(RefCell::new(values)),
+            listeners: Listeners::new(),
+        };
+    }
+
+    /// Call `cb` with each change after it's applied, until the returned value is
+    /// dropped.
+    pub fn listen(&self, cb: impl FnMut(&ObsVecSplice<T>) + 'static) -> ScopeValue {
+        return self.listeners.listen(cb);
+    }
+
+    /// Read the current values.
+    pub fn with<R>(&self, f: impl FnOnce(&[T]) -> R) -> R {
+        return f(&self.values.borrow());
+    }
+
+    pub fn get(&self, i: usize) -> Option<T> {
+        return self.values.borrow().get(i).cloned();
+    }
+
+    pub fn len(&self) -> usize {
+        return self.values.borrow().len();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.values.borrow().is_empty();
+    }
+
+    /// Replace `remove` values at `offset` with `add`, returning the removed values.
+    pub fn splice(&self, offset: usize, remove: usize, add: Vec<T>) -> Vec<T> {
+        let removed = self.values.borrow_mut().splice(offset .. offset + remove, add.clone()).collect::<Vec<_>>();
+        self.listeners.emit(&ObsVecSplice {
+            offset,
+            remove,
+            add,
+        });
+        return removed;
+    }
+
+    pub fn push(&self, value: T) {
+        let len = self.len();
+        self.splice(len, 0, vec![value]);
+    }
+
+    pub fn extend(&self, values: Vec<T>) {
+        let len = self.len();
+        self.splice(len, 0, values);
+    }
+
+    pub fn insert(&self, i: usize, value: T) {
+        self.splice(i, 0, vec![value]);
+    }
+
+    /// Replace the value at `i`.
+    pub fn set(&self, i: usize, value: T) {
+        self.splice(i, 1, vec![value]);
+    }
+
+    pub fn remove(&self, i: usize) -> T {
+        return self.splice(i, 1, vec![]).pop().unwrap();
+    }
+
+    pub fn pop(&self) -> Option<T> {
+        let len = self.len();
+        if len == 0 {
+            return None;
+        }
+        return self.splice(len - 1, 1, vec![]).pop();
+    }
+
+    pub fn clear(&self) {
+        let len = self.len();
+        self.splice(0, len, vec![]);
+    }
+}
+
+impl<T: ContainerEntry + 'static> Container<T> {
+    /// Mirror the list into a new container in `el`, building an entry with `build`
+    /// for each value.  Changes to the list are applied as splices, so unchanged
+    /// entries are kept.  The subscription is owned by `el` and stops once the
+    /// returned container is dropped.
+    pub fn bind<
+        V: Clone + 'static,
+    >(el: El, values: &ObsVec<V>, build: impl Fn(&V) -> T + 'static) -> Rc<RefCell<Container<T>>> {
+        let container = Rc::new(RefCell::new(Container::new(el.clone())));
+        container.borrow_mut().extend(values.with(|v| v.iter().map(&build).collect()));
+        let weak = Rc::downgrade(&container);
+        let subscription = values.listen(move |change| {
+            let Some(container) = weak.upgrade() else {
+                return;
+            };
+            let add = change.add.iter().map(&build).collect::<Vec<_>>();
+            let removed = container.borrow_mut().splice(change.offset, change.remove, add).collect::<Vec<_>>();
+            drop(removed);
+        });
+        el.ref_own(|_| subscription);
+        return container;
+    }
+}
+
+/// A change to an `ObsMap`.
+#[derive(Clone, Debug)]
+pub enum ObsMapChange<K, V> {
+    Insert(K, V),
+    Update(K, V),
+    Remove(K),
+}
+
+/// A shared map that notifies listeners of each change, so views can update just
+/// the affected key (see `ContainerMap::bind`).  Clones refer to the same map.
+pub struct ObsMap<K, V> {
+    values: Rc<RefCell<HashMap<K, V>>>,
+    listeners: Listeners<ObsMapChange<K, V>>,
+}
+
+impl<K, V> Clone for ObsMap<K, V> {
+    fn clone(&self) -> Self {
+        return ObsMap {
+            values: self.values.clone(),
+            listeners: self.listeners.clone(),
+        };
+    }
+}
+
+impl<K: Eq + Hash + Clone + 'static, V: Clone + 'static> Default for ObsMap<K, V> {
+    fn default() -> Self {
+        return Self::new();
+    }
+}
+
+impl<K: Eq + Hash + Clone + 'static, V: Clone + 'static> ObsMap<K, V> {
+    pub fn new() -> ObsMap<K, V> {
+        return ObsMap {
+            values: Rc::new(RefCell::new(HashMap::new())),
+            listeners: Listeners::new(),
+        };
+    }
+
+    /// Call `cb` with each change after it's applied, until the returned value is
+    /// dropped.
+    pub fn listen(&self, cb: impl FnMut(&ObsMapChange<K, V>) + 'static) -> ScopeValue {
+        return self.listeners.listen(cb);
+    }
+
+    /// Read the current values.
+    pub fn with<R>(&self, f: impl FnOnce(&HashMap<K, V>) -> R) -> R {
+        return f(&self.values.borrow());
+    }
+
+    pub fn get(&self, key: &K) -> Option<V> {
+        return self.values.borrow().get(key).cloned();
+    }
+
+    pub fn contains_key(&self, key: &K) -> bool {
+        return self.values.borrow().contains_key(key);
+    }
+
+    pub fn len(&self) -> usize {
+        return self.values.borrow().len();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.values.borrow().is_empty();
+    }
+
+    /// Add or replace the value for the key, returning the previous value.
+    pub fn insert(&self, key: K, value: V) -> Option<V> {
+        let previous = self.values.borrow_mut().insert(key.clone(), value.clone());
+        self.listeners.emit(&if previous.is_some() {
+            ObsMapChange::Update(key, value)
+        } else {
+            ObsMapChange::Insert(key, value)
+        });
+        return previous;
+    }
+
+    pub fn remove(&self, key: &K) -> Option<V> {
+        let previous = self.values.borrow_mut().remove(key)?;
+        self.listeners.emit(&ObsMapChange::Remove(key.clone()));
+        return Some(previous);
+    }
+
+    pub fn clear(&self) {
+        let keys = self.values.borrow().keys().cloned().collect::<Vec<_>>();
+        for k in keys {
+            self.remove(&k);
+        }
+    }
+}
+
+impl<K: Eq + Hash + Clone + 'static, T: ContainerEntry + 'static> ContainerMap<K, T> {
+    /// Mirror the map into a new keyed container in `el`, building an entry with
+    /// `build` for each value.  Only the entry for a changed key is rebuilt.  The
+    /// subscription is owned by `el` and stops once the returned map is dropped.
+    pub fn bind<
+        V: Clone + 'static,
+    >(el: El, values: &ObsMap<K, V>, build: impl Fn(&K, &V) -> T + 'static) -> Rc<RefCell<ContainerMap<K, T>>> {
+        let map = Rc::new(RefCell::new(ContainerMap::new(el.clone())));
+        values.with(|values| {
+            let mut map = map.borrow_mut();
+            for (k, v) in values {
+                map.insert(k.clone(), build(k, v));
+            }
+        });
+        let weak = Rc::downgrade(&map);
+        let subscription = values.listen(move |change| {
+            let Some(map) = weak.upgrade() else {
+                return;
+            };
+            let old = match change {
+                ObsMapChange::Insert(k, v) | ObsMapChange::Update(k, v) => {
+                    let entry = build(k, v);
+                    map.borrow_mut().insert(k.clone(), entry)
+                },
+                ObsMapChange::Remove(k) => map.borrow_mut().remove(k),
+            };
+            drop(old);
+        });
+        el.ref_own(|_| subscription);
+        return map;
+    }
+}
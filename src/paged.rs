@@ -0,0 +1,178 @@
+use std::{
+    cell::RefCell,
+    future::Future,
+    pin::Pin,
+    rc::Rc,
+};
+use crate::{
+    spawn_rooted_then,
+    ContainerEntry,
+    El,
+    Listeners,
+    ScopeValue,
+};
+
+/// One page of entries produced by a `PagedContainer` loader.
+pub struct Page<T> {
+    pub entries: Vec<T>,
+    /// Whether there are pages after this one.
+    pub has_more: bool,
+}
+
+type PageLoader<T> = Rc<dyn Fn(usize) -> Pin<Box<dyn Future<Output = Page<T>>>>>;
+
+struct PagedContainer_<T> {
+    load: PageLoader<T>,
+    placeholder: Rc<dyn Fn() -> El>,
+    page: usize,
+    entries: Vec<T>,
+    has_more: bool,
+    pending: bool,
+    task: Option<ScopeValue>,
+    loaded: Listeners<usize>,
+}
+
+/// Shows one page of entries at a time from a (possibly async) data source.  While
+/// a page is loading, a placeholder element is shown instead.  Changing pages
+/// cancels the load of the previous page, as does dropping the container.
+///
+/// Each clone refers to the same container.
+pub struct PagedContainer<T: ContainerEntry> {
+    el: El,
+    state: Rc<RefCell<PagedContainer_<T>>>,
+}
+
+impl<T: ContainerEntry> Clone for PagedContainer<T> {
+    fn clone(&self) -> Self {
+        return PagedContainer {
+            el: self.el.clone(),
+            state: self.state.clone(),
+        };
+    }
+}
+
+impl<T: ContainerEntry + 'static> PagedContainer<T> {
+    /// Create the container and start loading the first page (page `0`).  `load` is
+    /// called with a page number to produce that page's entries.  `placeholder` is
+    /// called to produce an element to show while a page is loading.
+    pub fn new<
+        F: Future<Output = Page<T>> + 'static,
+    >(el: El, load: impl Fn(usize) -> F + 'static, placeholder: impl Fn() -> El + 'static) -> Self {
+        let out = PagedContainer {
+            el,
+            state: Rc::new(RefCell::new(PagedContainer_ {
+                load: Rc::new(move |page| Box::pin(load(page))),
+                placeholder: Rc::new(placeholder),
+                page: 0,
+                entries: vec![],
+                has_more: false,
+                pending: false,
+                task: None,
+                loaded: Listeners::new(),
+            })),
+        };
+        out.set_page(0);
+        return out;
+    }
+
+    /// The current (or loading) page number.
+    pub fn page(&self) -> usize {
+        return self.state.borrow().page;
+    }
+
+    /// Whether the current page is still loading.
+    pub fn is_pending(&self) -> bool {
+        return self.state.borrow().pending;
+    }
+
+    /// Whether the loaded page reported that there are pages after it.
+    pub fn has_next(&self) -> bool {
+        let s = self.state.borrow();
+        return !s.pending && s.has_more;
+    }
+
+    pub fn has_prev(&self) -> bool {
+        return self.state.borrow().page > 0;
+    }
+
+    /// Call `f` with the current page's entries (empty while loading).
+    pub fn with_entries<U>(&self, f: impl FnOnce(&[T]) -> U) -> U {
+        return f(&self.state.borrow().entries);
+    }
+
+    /// Show the placeholder and start loading page `page`, canceling any load in
+    /// progress.
+    pub fn set_page(&self, page: usize) {
+        let old;
+        let make_placeholder;
+        let load;
+        {
+            let mut s = self.state.borrow_mut();
+            old = (s.task.take(), s.entries.split_off(0));
+            s.page = page;
+            s.pending = true;
+            s.has_more = false;
+            make_placeholder = s.placeholder.clone();
+            load = s.load.clone();
+        }
+        drop(old);
+
+        // Called after the borrow is released so they can use this container
+        let placeholder = make_placeholder();
+        let fut = load(page);
+        self.el.ref_clear();
+        self.el.ref_push(placeholder);
+        let state = Rc::downgrade(&self.state);
+        let el = self.el.clone();
+        let task = spawn_rooted_then(fut, move |loaded| {
+            let Some(state) = state.upgrade() else {
+                return;
+            };
+            let (els, listeners) = {
+                let mut s = state.borrow_mut();
+                s.pending = false;
+                s.has_more = loaded.has_more;
+                let els = loaded.entries.iter().map(|e| e.el().clone()).collect::<Vec<_>>();
+                s.entries = loaded.entries;
+                (els, s.loaded.clone())
+            };
+            el.ref_clear();
+            el.ref_extend(els);
+            listeners.emit(&page);
+        });
+        self.state.borrow_mut().task = Some(task);
+    }
+
+    /// Load the next page, if the current page reported there are more.
+    pub fn next_page(&self) {
+        if !self.has_next() {
+            return;
+        }
+        self.set_page(self.page() + 1);
+    }
+
+    /// Load the previous page, if this isn't the first page.
+    pub fn prev_page(&self) {
+        if !self.has_prev() {
+            return;
+        }
+        self.set_page(self.page() - 1);
+    }
+
+    /// Load the current page again.
+    pub fn reload(&self) {
+        self.set_page(self.page());
+    }
+
+    /// Call `cb` with the page number each time a page finishes loading, until the
+    /// returned value is dropped.
+    pub fn on_load(&self, cb: impl FnMut(&usize) + 'static) -> ScopeValue {
+        return self.state.borrow().loaded.listen(cb);
+    }
+}
+
+impl<T: ContainerEntry> ContainerEntry for PagedContainer<T> {
+    fn el(&self) -> &El {
+        return &self.el;
+    }
+}
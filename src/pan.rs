@@ -0,0 +1,146 @@
+use std::{
+    cell::RefCell,
+    rc::Rc,
+};
+use js_sys::Date;
+use crate::{
+    schedule::animation_loop,
+    DragGesture,
+    El,
+    ScopeValue,
+};
+
+/// Options for `El::pannable`.
+#[derive(Clone, Copy, Debug)]
+pub struct PanOptions {
+    /// Keep scrolling after the pointer is released, slowing down over time.
+    pub momentum: bool,
+    /// Fraction of momentum velocity kept per millisecond, ex: 0.995.
+    pub friction: f64,
+    /// Scroll position limits `(min_x, min_y, max_x, max_y)`, in addition to the
+    /// element's scrollable range.
+    pub bounds: Option<(f64, f64, f64, f64)>,
+}
+
+impl Default for PanOptions {
+    fn default() -> Self {
+        return PanOptions {
+            momentum: true,
+            friction: 0.995,
+            bounds: None,
+        };
+    }
+}
+
+/// Stop momentum below this speed, in pixels per millisecond.
+const MIN_VELOCITY: f64 = 0.01;
+
+struct PanState {
+    start_scroll: (f64, f64),
+    /// The unrounded scroll position last set, so slow momentum isn't lost to the
+    /// element's integer scroll position.
+    scroll: (f64, f64),
+    last: (f64, f64, f64),
+    velocity: (f64, f64),
+    momentum: Option<ScopeValue>,
+}
+
+/// Scroll to the position, limited by the bounds, returning the new position.
+fn scroll_to(e: &El, options: &PanOptions, x: f64, y: f64) -> (f64, f64) {
+    let (x, y) = match options.bounds {
+        Some((min_x, min_y, max_x, max_y)) => (x.clamp(min_x, max_x), y.clamp(min_y, max_y)),
+        None => (x, y),
+    };
+    e.raw().scroll_to_with_x_and_y(x, y);
+    return (x, y);
+}
+
+impl El {
+    /// Scroll the element by dragging it with the pointer (ex: for maps or diagram
+    /// canvases), optionally continuing with momentum after release.  The listeners
+    /// and momentum animation are owned by the element.
+    pub fn pannable(self, options: PanOptions) -> Self {
+        self.ref_pannable(options);
+        return self;
+    }
+
+    pub fn ref_pannable(&self, options: PanOptions) -> &Self {
+        let state = Rc::new(RefCell::new(PanState {
+            start_scroll: (0., 0.),
+            scroll: (0., 0.),
+            last: (0., 0., 0.),
+            velocity: (0., 0.),
+            momentum: None,
+        }));
+        let e = self.weak();
+        self.ref_on_drag_gesture({
+            let state = state.clone();
+            move |g| {
+                let Some(e) = e.upgrade() else {
+                    return;
+                };
+                let now = Date::now();
+                match g {
+                    DragGesture::Start { x, y } => {
+                        let raw = e.raw();
+                        let old_momentum = {
+                            let mut s = state.borrow_mut();
+                            s.start_scroll = (raw.scroll_left() as f64, raw.scroll_top() as f64);
+                            s.scroll = s.start_scroll;
+                            s.last = (x, y, now);
+                            s.velocity = (0., 0.);
+                            s.momentum.take()
+                        };
+                        drop(old_momentum);
+                    },
+                    DragGesture::Move { x, y, dx, dy } => {
+                        let mut s = state.borrow_mut();
+                        let dt = now - s.last.2;
+                        if dt > 0. {
+                            s.velocity = ((s.last.0 - x) / dt, (s.last.1 - y) / dt);
+                        }
+                        s.last = (x, y, now);
+                        s.scroll = scroll_to(&e, &options, s.start_scroll.0 - dx, s.start_scroll.1 - dy);
+                    },
+                    DragGesture::End { .. } => {
+                        if !options.momentum {
+                            return;
+                        }
+                        let mut s = state.borrow_mut();
+
+                        // Pointer stopped before release
+                        if now - s.last.2 > 100. {
+                            return;
+                        }
+                        let mut velocity = s.velocity;
+                        let mut last_t = None;
+                        let e = e.weak();
+                        let state1 = Rc::downgrade(&state);
+                        s.momentum = Some(animation_loop(move |t| {
+                            let Some(e) = e.upgrade() else {
+                                return;
+                            };
+                            let Some(state) = state1.upgrade() else {
+                                return;
+                            };
+                            let dt = t - last_t.unwrap_or(t);
+                            last_t = Some(t);
+                            let decay = options.friction.powf(dt);
+                            velocity = (velocity.0 * decay, velocity.1 * decay);
+                            if velocity.0.hypot(velocity.1) < MIN_VELOCITY {
+                                let momentum = state.borrow_mut().momentum.take();
+                                drop(momentum);
+                                return;
+                            }
+                            let mut s = state.borrow_mut();
+                            s.scroll =
+                                scroll_to(&e, &options, s.scroll.0 + velocity.0 * dt, s.scroll.1 + velocity.1 * dt);
+                        }));
+                    },
+                    DragGesture::Cancel => { },
+                }
+            }
+        });
+        return self.ref_own(|_| state);
+    }
+}
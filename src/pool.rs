@@ -0,0 +1,89 @@
+use std::{
+    cell::RefCell,
+    rc::{
+        Rc,
+        Weak,
+    },
+};
+use web_sys::Element;
+use crate::{
+    el,
+    el_from_raw,
+    El,
+};
+
+pub(crate) struct ElPool_ {
+    tag: String,
+    free: Vec<Element>,
+    limit: usize,
+}
+
+pub(crate) type WeakPool = Weak<RefCell<ElPool_>>;
+
+/// Return an element's DOM node to its pool when the element is dropped.  The
+/// element's owned values and children must already be dropped.
+pub(crate) fn recycle(pool: &WeakPool, raw: &Element) {
+    let Some(pool) = pool.upgrade() else {
+        return;
+    };
+    let mut pool = pool.borrow_mut();
+    if pool.free.len() >= pool.limit {
+        return;
+    }
+    raw.remove();
+    for name in raw.get_attribute_names().iter().filter_map(|n| n.as_string()) {
+        _ = raw.remove_attribute(&name);
+    }
+    raw.set_text_content(None);
+    pool.free.push(raw.clone());
+}
+
+/// Recycles the DOM nodes of dropped elements for reuse, to reduce allocation in
+/// lists with a lot of churn (ex: rows in a rapidly scrolled list).  Get elements
+/// from the pool with `get` and use them as normal - when one is dropped its owned
+/// values and children are dropped as usual, then its DOM node is reset and kept
+/// for the next `get`.
+///
+/// Recycled nodes have their attributes and contents removed, but other state (ex:
+/// the value of an `input`, or properties set directly) is left as is, so avoid
+/// pooling elements with such state.
+///
+/// Each clone refers to the same pool.  Once all clones are dropped, elements from
+/// the pool are dropped normally.
+#[derive(Clone)]
+pub struct ElPool(Rc<RefCell<ElPool_>>);
+
+impl ElPool {
+    /// A pool of elements with the tag, keeping at most 1000 unused nodes.
+    pub fn new(tag: &str) -> Self {
+        return Self::with_limit(tag, 1000);
+    }
+
+    /// A pool of elements with the tag, keeping at most `limit` unused nodes.
+    pub fn with_limit(tag: &str, limit: usize) -> Self {
+        return ElPool(Rc::new(RefCell::new(ElPool_ {
+            tag: tag.to_string(),
+            free: vec![],
+            limit,
+        })));
+    }
+
+    /// The number of unused nodes in the pool.
+    pub fn free_len(&self) -> usize {
+        return self.0.borrow().free.len();
+    }
+
+    /// Get an element, reusing a recycled node if available.
+    pub fn get(&self) -> El {
+        let (raw, tag) = {
+            let mut pool = self.0.borrow_mut();
+            (pool.free.pop(), pool.tag.clone())
+        };
+        let out = match raw {
+            Some(raw) => el_from_raw(raw),
+            None => el(&tag),
+        };
+        out.set_pool(Rc::downgrade(&self.0));
+        return out;
+    }
+}
@@ -0,0 +1,215 @@
+use std::{
+    cell::RefCell,
+    rc::Rc,
+};
+use gloo_events::{
+    EventListener,
+    EventListenerOptions,
+    EventListenerPhase,
+};
+use gloo_utils::{
+    document,
+    window,
+};
+use wasm_bindgen::{
+    JsCast,
+    JsValue,
+};
+use web_sys::{
+    Element,
+    HtmlElement,
+    MouseEvent,
+    Node,
+};
+use crate::{
+    El,
+    ObserveHandle,
+    ResizeObserver,
+    Root,
+};
+
+/// Where to place a popup relative to its anchor.  If there isn't room on the
+/// requested side, the popup is placed on the opposite side.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Placement {
+    Top,
+    Bottom,
+    Left,
+    Right,
+}
+
+fn position(anchor: &Element, content: &Element, placement: Placement) {
+    let a = anchor.get_bounding_client_rect();
+    let c = content.get_bounding_client_rect();
+    let view_width = window().inner_width().ok().and_then(|w| w.as_f64()).unwrap_or(0.);
+    let view_height = window().inner_height().ok().and_then(|h| h.as_f64()).unwrap_or(0.);
+    let centered_x = a.left() + (a.width() - c.width()) / 2.;
+    let centered_y = a.top() + (a.height() - c.height()) / 2.;
+    let above = a.top() - c.height();
+    let below = a.bottom();
+    let before = a.left() - c.width();
+    let after = a.right();
+    let (x, y) = match placement {
+        Placement::Top => (centered_x, if above < 0. && below + c.height() <= view_height {
+            below
+        } else {
+            above
+        }),
+        Placement::Bottom => (centered_x, if below + c.height() > view_height && above >= 0. {
+            above
+        } else {
+            below
+        }),
+        Placement::Left => (if before < 0. && after + c.width() <= view_width {
+            after
+        } else {
+            before
+        }, centered_y),
+        Placement::Right => (if after + c.width() > view_width && before >= 0. {
+            before
+        } else {
+            after
+        }, centered_y),
+    };
+    let x = x.min(view_width - c.width()).max(0.);
+    let y = y.min(view_height - c.height()).max(0.);
+    let Some(content) = content.dyn_ref::<HtmlElement>() else {
+        return;
+    };
+    let style = content.style();
+    _ = style.set_property("position", "fixed");
+    _ = style.set_property("left", &format!("{}px", x));
+    _ = style.set_property("top", &format!("{}px", y));
+}
+
+/// An element shown on top of the page next to an anchor element.  The content is
+/// appended to the body (so it isn't clipped by the anchor's ancestors) and kept
+/// positioned next to the anchor as either element resizes or the page scrolls.
+///
+/// The content is removed when the `Popup` is dropped, so attach it to the anchor
+/// with `.own()` to remove it along with the anchor.
+pub struct Popup {
+    anchor: Element,
+    content: El,
+    placement: Placement,
+    _root: Root,
+    _resize: (ResizeObserver, ObserveHandle, ObserveHandle),
+    _scroll: EventListener,
+    _window_resize: EventListener,
+}
+
+impl Popup {
+    pub fn new(anchor: &El, content: El, placement: Placement) -> Popup {
+        let anchor = anchor.raw();
+        let body = document().body().map(Element::from).ok_or_else(|| JsValue::from_str("Document has no body"));
+        let root = Root::append_at(body, vec![content.clone()]);
+        let reposition = {
+            let anchor = anchor.clone();
+            let content = content.raw();
+            move || position(&anchor, &content, placement)
+        };
+        let resize_observer = ResizeObserver::new({
+            let reposition = reposition.clone();
+            move |_| reposition()
+        });
+        let anchor_handle = resize_observer.observe(&anchor);
+        let content_handle = resize_observer.observe(&content.raw());
+        let scroll = EventListener::new_with_options(&window(), "scroll", EventListenerOptions {
+            phase: EventListenerPhase::Capture,
+            passive: true,
+        }, {
+            let reposition = reposition.clone();
+            move |_| reposition()
+        });
+        let window_resize = EventListener::new(&window(), "resize", {
+            let reposition = reposition.clone();
+            move |_| reposition()
+        });
+        reposition();
+        return Popup {
+            anchor,
+            content,
+            placement,
+            _root: root,
+            _resize: (resize_observer, anchor_handle, content_handle),
+            _scroll: scroll,
+            _window_resize: window_resize,
+        };
+    }
+
+    pub fn content(&self) -> &El {
+        return &self.content;
+    }
+
+    /// Update the position manually, for instance if the anchor moved due to a
+    /// layout change that didn't resize either element.
+    pub fn reposition(&self) {
+        position(&self.anchor, &self.content.raw(), self.placement);
+    }
+}
+
+impl El {
+    /// Show `content` in a `Popup` next to this element while it's hovered or
+    /// focused.
+    pub fn tooltip(self, content: El, placement: Placement) -> Self {
+        self.ref_tooltip(content, placement);
+        return self;
+    }
+
+    pub fn ref_tooltip(&self, content: El, placement: Placement) -> &Self {
+        let shown = Rc::new(RefCell::new(None::<Popup>));
+        let listeners = ["mouseenter", "focusin", "mouseleave", "focusout"].map(|event| {
+            let show = event == "mouseenter" || event == "focusin";
+            let shown = shown.clone();
+            let anchor = self.weak();
+            let content = content.clone();
+            return EventListener::new(&self.raw(), event, move |_| {
+                if !show {
+                    let popup = shown.borrow_mut().take();
+                    drop(popup);
+                    return;
+                }
+                if shown.borrow().is_some() {
+                    return;
+                }
+                let Some(anchor) = anchor.upgrade() else {
+                    return;
+                };
+                let popup = Popup::new(&anchor, content.clone(), placement);
+                *shown.borrow_mut() = Some(popup);
+            });
+        });
+        return self.ref_own(|_| (shown, listeners));
+    }
+
+    /// Call `cb` when there's a click anywhere outside this element (ex: to close a
+    /// dropdown).  This uses the event's composed path so clicks inside shadow roots
+    /// within the element count as inside.  The document listener is owned by the
+    /// element.
+    pub fn on_click_outside(self, cb: impl FnMut(&MouseEvent) + 'static) -> Self {
+        self.ref_on_click_outside(cb);
+        return self;
+    }
+
+    pub fn ref_on_click_outside(&self, mut cb: impl FnMut(&MouseEvent) + 'static) -> &Self {
+        let raw = self.raw();
+        return self.ref_own(|_| EventListener::new_with_options(&document(), "click", EventListenerOptions {
+            phase: EventListenerPhase::Capture,
+            passive: true,
+        }, move |ev| {
+            let path = ev.composed_path();
+            let inside = if path.length() > 0 {
+                path.includes(&raw, 0)
+            } else {
+                ev.target().and_then(|t| t.dyn_into::<Node>().ok()).is_some_and(|t| raw.contains(Some(&t)))
+            };
+            if inside {
+                return;
+            }
+            let Some(ev) = ev.dyn_ref::<MouseEvent>() else {
+                return;
+            };
+            cb(ev);
+        }));
+    }
+}
@@ -0,0 +1,180 @@
+use std::{
+    cell::RefCell,
+    rc::Rc,
+};
+#[cfg(feature = "futures")]
+use futures::{
+    Future,
+    Stream,
+};
+use crate::{
+    scope_any,
+    Listeners,
+    ScopeValue,
+};
+#[cfg(feature = "futures")]
+use crate::spawn::{
+    own_stream,
+    spawn_rooted,
+};
+
+struct Prim_<T> {
+    value: RefCell<T>,
+    listeners: Listeners<T>,
+    /// Values kept alive as long as the primitive, like subscriptions to the
+    /// dependencies of a `computed` value.
+    owned: RefCell<Vec<ScopeValue>>,
+}
+
+/// A shared value that notifies listeners when it changes.  Clones refer to the same
+/// value.
+///
+/// Listeners are called after the value is updated, with a copy of the new value,
+/// so they can read or even set primitives (including this one).
+pub struct Prim<T>(Rc<Prim_<T>>);
+
+impl<T> Clone for Prim<T> {
+    fn clone(&self) -> Self {
+        return Prim(self.0.clone());
+    }
+}
+
+impl<T: Clone + 'static> Prim<T> {
+    pub fn new(value: T) -> Prim<T> {
+        return Prim(Rc::new(Prim_ {
+            value: RefCell::new(value),
+            listeners: Listeners::new(),
+            owned: RefCell::new(vec![]),
+        }));
+    }
+
+    /// A copy of the current value.
+    pub fn get(&self) -> T {
+        return self.0.value.borrow().clone();
+    }
+
+    /// Read the current value without copying it.
+    pub fn with<R>(&self, f: impl FnOnce(&T) -> R) -> R {
+        return f(&self.0.value.borrow());
+    }
+
+    /// Replace the value and notify listeners.
+    pub fn set(&self, value: T) {
+        *self.0.value.borrow_mut() = value;
+        self.notify();
+    }
+
+    /// Modify the value in place and notify listeners.
+    pub fn modify(&self, f: impl FnOnce(&mut T)) {
+        f(&mut self.0.value.borrow_mut());
+        self.notify();
+    }
+
+    fn notify(&self) {
+        let value = self.get();
+        self.0.listeners.emit(&value);
+    }
+
+    /// Call `cb` with the new value after each change, until the returned value is
+    /// dropped.
+    pub fn listen(&self, cb: impl FnMut(&T) + 'static) -> ScopeValue {
+        return self.0.listeners.listen(cb);
+    }
+
+    /// Keep `value` alive as long as this primitive.
+    pub fn own(&self, value: ScopeValue) {
+        self.0.owned.borrow_mut().push(value);
+    }
+}
+
+impl<T: Clone + PartialEq + 'static> Prim<T> {
+    /// Like `set` but only notifies listeners if the value changed.
+    pub fn set_if_changed(&self, value: T) {
+        if *self.0.value.borrow() == value {
+            return;
+        }
+        self.set(value);
+    }
+}
+
+/// A primitive of any type that can be depended on by `computed` and `effect`.
+pub trait PrimDep {
+    /// Call `cb` when the value changes, until the returned value is dropped.
+    fn listen_change(&self, cb: Box<dyn FnMut()>) -> ScopeValue;
+}
+
+impl<T: Clone + 'static> PrimDep for Prim<T> {
+    fn listen_change(&self, mut cb: Box<dyn FnMut()>) -> ScopeValue {
+        return self.listen(move |_| cb());
+    }
+}
+
+/// A primitive whose value is calculated by `f` from other primitives, recalculated
+/// whenever any of `deps` changes.  `f` should read the dependencies with `get` or
+/// `with`.  Listeners are only notified if the result changes.
+pub fn computed<T: Clone + PartialEq + 'static>(deps: &[&dyn PrimDep], f: impl Fn() -> T + 'static) -> Prim<T> {
+    let out = Prim::new(f());
+    let f = Rc::new(f);
+    for dep in deps {
+        let weak = Rc::downgrade(&out.0);
+        let f = f.clone();
+        out.own(dep.listen_change(Box::new(move || {
+            let Some(out) = weak.upgrade() else {
+                return;
+            };
+            Prim(out).set_if_changed(f());
+        })));
+    }
+    return out;
+}
+
+/// Call `f` now and again whenever any of `deps` changes, until the returned value is
+/// dropped.  Attach it to an element with `.own()` to stop the effect when the
+/// element is removed.
+pub fn effect(deps: &[&dyn PrimDep], f: impl FnMut() + 'static) -> ScopeValue {
+    let f = Rc::new(RefCell::new(f));
+    (f.borrow_mut())();
+    let mut subscriptions = vec![];
+    for dep in deps {
+        let f = f.clone();
+        subscriptions.push(dep.listen_change(Box::new(move || {
+            // Skip if re-entered (the effect changed one of its own dependencies)
+            let Ok(mut f) = f.try_borrow_mut() else {
+                return;
+            };
+            f();
+        })));
+    }
+    return scope_any(subscriptions);
+}
+
+/// A primitive updated with each value from the stream.  The task reading the stream
+/// is owned by the primitive, so it stops when the last clone is dropped.
+#[cfg(feature = "futures")]
+pub fn prim_from_stream<T: Clone + 'static>(initial: T, stream: impl Stream<Item = T> + 'static) -> Prim<T> {
+    let out = Prim::new(initial);
+    let weak = Rc::downgrade(&out.0);
+    out.own(own_stream(stream, move |v| {
+        let Some(out) = weak.upgrade() else {
+            return;
+        };
+        Prim(out).set(v);
+    }));
+    return out;
+}
+
+/// A primitive with the value `initial` until the future resolves, then the future's
+/// output.  The future is canceled if the last clone is dropped first.
+#[cfg(feature = "futures")]
+pub fn prim_from_future<T: Clone + 'static>(initial: T, f: impl Future<Output = T> + 'static) -> Prim<T> {
+    let out = Prim::new(initial);
+    let weak = Rc::downgrade(&out.0);
+    out.own(spawn_rooted(async move {
+        let v = f.await;
+        let Some(out) = weak.upgrade() else {
+            return;
+        };
+        Prim(out).set(v);
+    }));
+    return out;
+}
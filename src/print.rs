@@ -0,0 +1,117 @@
+use std::{
+    cell::{
+        Cell,
+        RefCell,
+    },
+    rc::Rc,
+};
+use gloo_events::EventListener;
+use gloo_utils::{
+    document,
+    window,
+};
+use wasm_bindgen::{
+    JsCast,
+    JsValue,
+};
+use web_sys::{
+    Element,
+    HtmlIFrameElement,
+};
+use crate::{
+    error::ReportExt,
+    scope_any,
+    El,
+    ScopeValue,
+};
+
+thread_local!{
+    // The frame for the current print job, removed after printing finishes, and its
+    // listeners
+    static PRINT_FRAME: RefCell<Option<(Element, Vec<EventListener>)>> = const {
+        RefCell::new(None)
+    };
+}
+
+fn remove_print_frame() {
+    let frame = PRINT_FRAME.with(|f| f.borrow_mut().take());
+    if let Some((frame, _listeners)) = frame {
+        frame.remove();
+    }
+}
+
+/// Print just the element's subtree.  The element's current html is copied into a
+/// hidden frame along with the page's styles (`<style>` and `<link
+/// rel="stylesheet">` elements matching `styles_selector`, or all of them if
+/// `None`), and the frame is printed once the stylesheets load and removed
+/// afterwards.
+pub fn print_element(el: &El, styles_selector: Option<&str>) {
+    remove_print_frame();
+    (|| {
+        let body = document().body().ok_or_else(|| JsValue::from_str("Document has no body"))?;
+        let frame = document().create_element("iframe")?.dyn_into::<HtmlIFrameElement>()?;
+        frame.set_attribute("aria-hidden", "true")?;
+        frame.set_attribute("style", "position: fixed; width: 0; height: 0; border: 0; visibility: hidden;")?;
+        body.append_child(&frame)?;
+        let frame_doc = frame.content_document().ok_or_else(|| JsValue::from_str("Print frame has no document"))?;
+        let frame_win = frame.content_window().ok_or_else(|| JsValue::from_str("Print frame has no window"))?;
+        let mut styles = String::new();
+        let styles_found = document().query_selector_all(r#"style, link[rel="stylesheet"]"#)?;
+        for i in 0 .. styles_found.length() {
+            let Some(s) = styles_found.get(i).and_then(|s| s.dyn_into::<Element>().ok()) else {
+                continue;
+            };
+            if let Some(selector) = styles_selector {
+                if !s.matches(selector)? {
+                    continue;
+                }
+            }
+            styles.push_str(&s.outer_html());
+        }
+        frame_doc.document_element().ok_or_else(|| JsValue::from_str("Print frame has no root element"))?.set_inner_html(
+            &format!("<head>{}</head><body>{}</body>", styles, el.raw().outer_html()),
+        );
+        let mut listeners = vec![EventListener::once(&frame_win, "afterprint", |_| {
+            remove_print_frame();
+        })];
+
+        // Print after the copied stylesheets load (or fail), otherwise the first print is
+        // usually unstyled
+        let links = frame_doc.query_selector_all(r#"link[rel="stylesheet"]"#)?;
+        let remaining = Rc::new(Cell::new(links.length()));
+        for i in 0 .. links.length() {
+            let Some(link) = links.get(i) else {
+                continue;
+            };
+            for event in ["load", "error"] {
+                listeners.push(EventListener::once(&link, event, {
+                    let remaining = remaining.clone();
+                    let frame_win = frame_win.clone();
+                    move |_| {
+                        remaining.set(remaining.get() - 1);
+                        if remaining.get() == 0 {
+                            frame_win.print().or_report();
+                        }
+                    }
+                }));
+            }
+        }
+        PRINT_FRAME.with(|f| *f.borrow_mut() = Some((frame.into(), listeners)));
+        if remaining.get() == 0 {
+            frame_win.print()?;
+        }
+        return Ok(());
+    })().or_report();
+}
+
+/// Call `cb` just before the page is printed (ex: to expand collapsed sections),
+/// until the returned value is dropped.
+pub fn on_before_print(mut cb: impl FnMut() + 'static) -> ScopeValue {
+    return scope_any(EventListener::new(&window(), "beforeprint", move |_| cb()));
+}
+
+/// Call `cb` after the page is printed or printing is canceled, until the returned
+/// value is dropped.
+pub fn on_after_print(mut cb: impl FnMut() + 'static) -> ScopeValue {
+    return scope_any(EventListener::new(&window(), "afterprint", move |_| cb()));
+}
@@ -42,7 +42,7 @@ impl ResizeObserver {
     /// The callback receives an array with all observed elements that changed size
     /// this tick. You can retrieve the new sizes like:
     ///
-    /// ```
+    /// ```ignore
     /// let entry = entries.get(0).dyn_into::<ResizeObserverEntry>().unwrap();
     /// let size = entry.content_box_size().get(0).dyn_into::<ResizeObserverSize>().unwrap();
     /// (size.inline_size(), size.block_size())
@@ -1,25 +1,290 @@
-use std::cell::Cell;
-use gloo_utils::document;
-use wasm_bindgen::UnwrapThrowExt;
-use crate::El;
+use std::{
+    cell::RefCell,
+    fmt::Display,
+};
+use wasm_bindgen::JsValue;
+use web_sys::{
+    Document,
+    Element,
+};
+use crate::{
+    El,
+    WeakEl,
+    current_document,
+    error::ReportExt,
+    instrument::trace,
+    own::{
+        ScopeValue,
+        scope_any,
+    },
+};
+
+/// Something that identifies an existing element to mount a `Root` at: either an
+/// element id (looked up in the current document, see `with_document`) or an
+/// element.
+pub trait MountTarget {
+    fn mount_target(self) -> Result<Element, JsValue>;
+}
+
+impl MountTarget for &str {
+    fn mount_target(self) -> Result<Element, JsValue> {
+        return current_document()
+            .get_element_by_id(self)
+            .ok_or_else(|| JsValue::from_str(&format!("No element with id [{}] to mount at", self)));
+    }
+}
+
+impl MountTarget for &String {
+    fn mount_target(self) -> Result<Element, JsValue> {
+        return self.as_str().mount_target();
+    }
+}
+
+impl MountTarget for Element {
+    fn mount_target(self) -> Result<Element, JsValue> {
+        return Ok(self);
+    }
+}
+
+impl MountTarget for &Element {
+    fn mount_target(self) -> Result<Element, JsValue> {
+        return Ok(self.clone());
+    }
+}
+
+impl MountTarget for Result<Element, JsValue> {
+    fn mount_target(self) -> Result<Element, JsValue> {
+        return self;
+    }
+}
+
+thread_local!{
+    static MOUNTED: RefCell<Vec<WeakEl>> = const {
+        RefCell::new(vec![])
+    };
+}
+
+fn set_mounted(elements: &[El], mounted: bool) {
+    for e in elements {
+        e.0.borrow_mut().mounted = mounted;
+    }
+    MOUNTED.with(|m| {
+        let mut m = m.borrow_mut();
+        m.retain(|w| w.upgrade().is_some_and(|w| !elements.iter().any(|e| e.ptr_id() == w.ptr_id())));
+        if mounted {
+            m.extend(elements.iter().map(|e| e.weak()));
+        }
+    });
+}
+
+/// The top-level elements of all `Root`s (including `set_root` and similar)
+/// currently mounted in the document, in the order they were mounted.  For
+/// debugging tools.
+pub fn mounted_roots() -> Vec<El> {
+    return MOUNTED.with(|m| m.borrow().iter().filter_map(|w| w.upgrade()).collect());
+}
+
+/// A set of elements mounted into the document, owning their lifetimes.  When the
+/// `Root` is dropped, the elements are removed from the document and dropped.
+///
+/// If mounting fails (ex: the target doesn't exist) the error goes to the error
+/// handler (see `set_error_handler`) and the `Root` owns the unmounted elements.
+///
+/// Roots are independent of each other and of `set_root`, so you can mount several
+/// into different places in a page.
+pub struct Root {
+    elements: Vec<El>,
+}
+
+impl Root {
+    /// Sets the elements as the children of the target, replacing any existing
+    /// children.
+    pub fn mount_at(target: impl MountTarget, elements: Vec<El>) -> Root {
+        if let Some(target) = target.mount_target().or_report() {
+            target.replace_children_with_node(&elements.iter().map(|e| e.0.borrow().el.clone()).collect());
+            for e in &elements {
+                trace("mount", e);
+            }
+            set_mounted(&elements, true);
+        }
+        return Root { elements };
+    }
+
+    /// Appends the elements to the target's children, leaving existing children in
+    /// place.
+    pub fn append_at(target: impl MountTarget, elements: Vec<El>) -> Root {
+        if let Some(target) = target.mount_target().or_report() {
+            for e in &elements {
+                trace("mount", e);
+                target.append_child(&e.0.borrow().el).or_report();
+            }
+            set_mounted(&elements, true);
+        }
+        return Root { elements };
+    }
+
+    /// Replaces the target element itself with the new element.
+    pub fn replace(target: impl MountTarget, el: El) -> Root {
+        if let Some(target) = target.mount_target().or_report() {
+            trace("mount", &el);
+            if target.replace_with_with_node_1(&el.0.borrow().el).or_report().is_some() {
+                set_mounted(std::slice::from_ref(&el), true);
+            }
+        }
+        return Root { elements: vec![el] };
+    }
+
+    /// The mounted elements.
+    pub fn elements(&self) -> &[El] {
+        return &self.elements;
+    }
+}
+
+impl Drop for Root {
+    fn drop(&mut self) {
+        for e in &self.elements {
+            trace("unmount", e);
+            e.raw().remove();
+        }
+        set_mounted(&self.elements, false);
+    }
+}
 
 thread_local!{
-    static ROOT: Cell<Vec<El>> = Cell::new(vec![]);
+    /// Roots set with `set_root` and similar, per document.
+    static ROOT: RefCell<Vec<(Document, Vec<Root>)>> = const {
+        RefCell::new(vec![])
+    };
 }
 
+/// Removes the roots for `doc` from the registry without dropping them.
+fn take_roots(doc: &Document) -> Vec<Root> {
+    return ROOT.with(|r| {
+        let mut r = r.borrow_mut();
+        let i = r.iter().position(|(d, _)| d == doc)?;
+        return Some(r.remove(i).1);
+    }).unwrap_or_default();
+}
+
+fn push_root(doc: &Document, root: Root) {
+    ROOT.with(|r| {
+        let mut r = r.borrow_mut();
+        match r.iter_mut().find(|(d, _)| d == doc) {
+            Some((_, roots)) => roots.push(root),
+            None => r.push((doc.clone(), vec![root])),
+        }
+    });
+}
+
+/// Drops the current roots for `doc` before anything new is attached, so their
+/// cleanup runs first.  The old roots are taken out of the registry before being
+/// dropped so cleanup code can safely interact with the root.
+fn drop_root(doc: &Document) {
+    let old = take_roots(doc);
+    drop(old);
+}
+
+#[derive(Debug)]
+pub enum RootError {
+    /// There's no element with the id.
+    NotFound(String),
+    /// The DOM operation failed.
+    Js(JsValue),
+}
+
+impl Display for RootError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RootError::NotFound(id) => return write!(f, "No element with id [{}] to mount at", id),
+            RootError::Js(e) => return write!(f, "Mounting failed: {:?}", e),
+        }
+    }
+}
+
+impl std::error::Error for RootError { }
+
 /// Replaces the existing element with id `id`, taking ownership and extending the
-/// new element's lifetime.
+/// new element's lifetime.  Any previous root is dropped first.
+///
+/// If the element is missing the error goes to the error handler (see
+/// `set_error_handler`).  See `try_set_root_replace` to handle it yourself.
 pub fn set_root_replace(id: &str, el: El) {
-    document().get_element_by_id(id).unwrap().replace_with_with_node_1(&el.0.borrow().el).unwrap_throw();
-    ROOT.with(|r| r.set(vec![el]));
+    let doc = current_document();
+    drop_root(&doc);
+    let root = Root::replace(id, el);
+    push_root(&doc, root);
+}
+
+/// Like `set_root_replace` but replaces `target` rather than looking it up by id.
+pub fn set_root_replace_el(target: &Element, el: El) {
+    let doc = target.owner_document().unwrap_or_else(current_document);
+    drop_root(&doc);
+    let root = Root::replace(target, el);
+    push_root(&doc, root);
+}
+
+/// Like `set_root_replace` but returns an error if there's no element with id `id`
+/// or it can't be replaced.  If the element is missing the previous root is left in
+/// place.
+pub fn try_set_root_replace(id: &str, el: El) -> Result<(), RootError> {
+    let doc = current_document();
+    let target = doc.get_element_by_id(id).ok_or_else(|| RootError::NotFound(id.to_string()))?;
+    drop_root(&doc);
+    trace("mount", &el);
+    target.replace_with_with_node_1(&el.raw()).map_err(RootError::Js)?;
+    set_mounted(std::slice::from_ref(&el), true);
+    push_root(&doc, Root { elements: vec![el] });
+    return Ok(());
 }
 
 /// Sets the elements as the children of the body, taking ownership and their
-/// lifetimes.
+/// lifetimes.  Any previous root is dropped first.
+///
+/// This and the other root functions apply to the current document (see
+/// `with_document`).
 pub fn set_root(elements: Vec<El>) {
-    document()
-        .body()
-        .unwrap()
-        .replace_children_with_node(&elements.iter().map(|e| e.0.borrow().el.clone()).collect());
-    ROOT.with(|r| r.set(elements));
+    set_root_in(&current_document(), elements);
+}
+
+/// Like `set_root` but for the body of `doc`, for instance the document of a
+/// window opened with `window.open` or of an iframe.  Each document has its own
+/// roots, so this leaves roots in other documents in place.  Call `clear_root_in`
+/// when the window closes or the iframe is removed to drop the elements.
+pub fn set_root_in(doc: &Document, elements: Vec<El>) {
+    drop_root(doc);
+    let body = doc.body().map(Element::from).ok_or_else(|| JsValue::from_str("Document has no body"));
+    let root = Root::mount_at(body, elements);
+    push_root(doc, root);
+}
+
+/// Appends the elements to the children of the element with id `parent_id`,
+/// taking ownership and extending their lifetimes.  Unlike `set_root`, existing
+/// children and roots are left in place.
+pub fn set_root_append(parent_id: &str, elements: Vec<El>) {
+    let root = Root::append_at(parent_id, elements);
+    push_root(&current_document(), root);
+}
+
+/// Appends the elements to the parent's children. The elements are removed from
+/// the document and dropped when the returned value is dropped.
+pub fn mount_in(parent: &Element, elements: Vec<El>) -> ScopeValue {
+    return scope_any(Root::append_at(parent, elements));
+}
+
+/// Removes the root elements from the document and drops them.  Use this for
+/// deterministic teardown, for instance to cancel all tasks when logging out.
+pub fn clear_root() {
+    drop_root(&current_document());
+}
+
+/// Like `clear_root` but for the roots of `doc` (see `set_root_in`).
+pub fn clear_root_in(doc: &Document) {
+    drop_root(doc);
+}
+
+/// Takes ownership of the current root elements without removing them from the
+/// document. When the returned value is dropped the elements are removed from the
+/// document and dropped.
+pub fn take_root() -> ScopeValue {
+    return scope_any(take_roots(&current_document()));
 }
@@ -0,0 +1,195 @@
+use std::{
+    cell::RefCell,
+    rc::Rc,
+};
+use gloo_events::EventListener;
+use js_sys::{
+    Array,
+    Date,
+};
+use wasm_bindgen::{
+    prelude::Closure,
+    JsCast,
+    JsValue,
+};
+use web_sys::{
+    Element,
+    HtmlElement,
+    KeyboardEvent,
+    MutationObserver,
+    MutationObserverInit,
+    Node,
+};
+use crate::{
+    error::ReportExt,
+    scope_any,
+    El,
+    ScopeValue,
+};
+
+/// Which arrow keys move focus in a `RovingFocus`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum RovingOrientation {
+    /// Left and right
+    Horizontal,
+    /// Up and down
+    Vertical,
+    /// All arrow keys
+    Both,
+}
+
+/// Typeahead keys typed within this many milliseconds are combined.
+const TYPEAHEAD_MS: f64 = 500.;
+
+fn children(container: &Element) -> Vec<HtmlElement> {
+    let c = container.children();
+    return (0 .. c.length()).filter_map(|i| c.item(i)?.dyn_into::<HtmlElement>().ok()).collect();
+}
+
+/// Make `active` the only focusable child (or the first child if `active` isn't a
+/// child).
+fn set_active(container: &Element, active: Option<&Node>) {
+    let children = children(container);
+    let active = active.and_then(|a| children.iter().position(|c| AsRef::<Node>::as_ref(c) == a)).unwrap_or(0);
+    for (i, c) in children.iter().enumerate() {
+        c.set_tab_index(if i == active {
+            0
+        } else {
+            -1
+        });
+    }
+}
+
+/// Keyboard navigation among the children of a container, as for menus, listboxes
+/// and toolbars: only one child is in the tab order at a time, arrow keys and
+/// Home/End move focus between children, and typing focuses the next child whose
+/// text starts with the typed characters.
+///
+/// Children are read from the DOM so this follows container changes; when children
+/// are added or removed the tab order is fixed up.  Attach it to the container with
+/// `.own()`, or use `El::roving_focus`.
+pub struct RovingFocus {
+    _keydown: EventListener,
+    _focusin: EventListener,
+    observer: Option<(MutationObserver, ScopeValue)>,
+}
+
+impl RovingFocus {
+    pub fn new(container: &El, orientation: RovingOrientation) -> RovingFocus {
+        let raw = container.raw();
+        set_active(&raw, None);
+        let typeahead = Rc::new(RefCell::new((String::new(), 0f64)));
+        let keydown = EventListener::new(&raw, "keydown", {
+            let raw = raw.clone();
+            move |ev| {
+                let ev = ev.unchecked_ref::<KeyboardEvent>();
+                let children = children(&raw);
+                if children.is_empty() {
+                    return;
+                }
+                let current =
+                    ev
+                        .target()
+                        .and_then(|t| t.dyn_into::<Node>().ok())
+                        .and_then(|t| children.iter().position(|c| c.contains(Some(&t))));
+                let Some(current) = current else {
+                    return;
+                };
+                let horizontal = orientation != RovingOrientation::Vertical;
+                let vertical = orientation != RovingOrientation::Horizontal;
+                let key = ev.key();
+                let next = match key.as_str() {
+                    "ArrowRight" if horizontal => Some((current + 1) % children.len()),
+                    "ArrowDown" if vertical => Some((current + 1) % children.len()),
+                    "ArrowLeft" if horizontal => Some((current + children.len() - 1) % children.len()),
+                    "ArrowUp" if vertical => Some((current + children.len() - 1) % children.len()),
+                    "Home" => Some(0),
+                    "End" => Some(children.len() - 1),
+                    _ if key.chars().count() == 1 && !ev.ctrl_key() && !ev.alt_key() && !ev.meta_key() => {
+                        let mut typeahead = typeahead.borrow_mut();
+                        let now = Date::now();
+                        if now - typeahead.1 > TYPEAHEAD_MS {
+                            typeahead.0.clear();
+                        }
+                        typeahead.0.push_str(&key.to_lowercase());
+                        typeahead.1 = now;
+
+                        // Search from the current child when refining the prefix, otherwise
+                        // from the next one
+                        let start = if typeahead.0.chars().count() > 1 {
+                            current
+                        } else {
+                            current + 1
+                        };
+                        (0 .. children.len()).map(|i| (start + i) % children.len()).find(|i| {
+                            children[*i]
+                                .text_content()
+                                .unwrap_or_default()
+                                .trim_start()
+                                .to_lowercase()
+                                .starts_with(&typeahead.0)
+                        })
+                    },
+                    _ => None,
+                };
+                let Some(next) = next else {
+                    return;
+                };
+                ev.prevent_default();
+                set_active(&raw, Some(&children[next]));
+                children[next].focus().or_report();
+            }
+        });
+        let focusin = EventListener::new(&raw, "focusin", {
+            let raw = raw.clone();
+            move |ev| {
+                let Some(target) = ev.target().and_then(|t| t.dyn_into::<Node>().ok()) else {
+                    return;
+                };
+                if let Some(child) = children(&raw).into_iter().find(|c| c.contains(Some(&target))) {
+                    set_active(&raw, Some(&child));
+                }
+            }
+        });
+        let js_cb = Closure::wrap(Box::new({
+            let raw = raw.clone();
+            move |_: Array, _: JsValue| {
+                // Keep the active child if it's still present
+                let children = children(&raw);
+                let active = children.iter().find(|c| c.tab_index() == 0).cloned();
+                set_active(&raw, active.as_ref().map(|a| a.as_ref()));
+            }
+        }) as Box<dyn Fn(Array, JsValue)>);
+        let observer = MutationObserver::new(js_cb.as_ref().unchecked_ref()).or_report().map(|observer| {
+            let opts = MutationObserverInit::new();
+            opts.set_child_list(true);
+            observer.observe_with_options(&raw, &opts).or_report();
+            (observer, scope_any(js_cb))
+        });
+        return RovingFocus {
+            _keydown: keydown,
+            _focusin: focusin,
+            observer,
+        };
+    }
+}
+
+impl Drop for RovingFocus {
+    fn drop(&mut self) {
+        if let Some((observer, _)) = &self.observer {
+            observer.disconnect();
+        }
+    }
+}
+
+impl El {
+    /// Add keyboard navigation among this element's children, see `RovingFocus`.
+    pub fn roving_focus(self, orientation: RovingOrientation) -> Self {
+        self.ref_roving_focus(orientation);
+        return self;
+    }
+
+    pub fn ref_roving_focus(&self, orientation: RovingOrientation) -> &Self {
+        return self.ref_own(|e| RovingFocus::new(e, orientation));
+    }
+}
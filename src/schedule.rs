@@ -0,0 +1,194 @@
+use std::{
+    cell::{
+        Cell,
+        RefCell,
+    },
+    rc::{
+        Rc,
+        Weak,
+    },
+};
+use gloo_utils::window;
+use js_sys::Reflect;
+use wasm_bindgen::{
+    prelude::Closure,
+    JsCast,
+    JsValue,
+};
+use crate::{
+    own::{
+        ScopeValue,
+        defer,
+        scope_any,
+    },
+    El,
+    WeakEl,
+};
+
+/// Call `cb` once after `ms` milliseconds (`setTimeout`).  If the returned scope
+/// value is dropped first the timeout is canceled.
+pub fn timeout(ms: u32, cb: impl FnOnce() + 'static) -> ScopeValue {
+    let cb = Closure::once(cb);
+    let id =
+        window()
+            .set_timeout_with_callback_and_timeout_and_arguments_0(cb.as_ref().unchecked_ref(), ms as i32)
+            .unwrap();
+    return defer(move || {
+        window().clear_timeout_with_handle(id);
+        drop(cb);
+    });
+}
+
+/// Call `cb` every `ms` milliseconds (`setInterval`) until the returned scope value
+/// is dropped.
+pub fn interval(ms: u32, cb: impl FnMut() + 'static) -> ScopeValue {
+    let cb = Closure::wrap(Box::new(cb) as Box<dyn FnMut()>);
+    let id =
+        window()
+            .set_interval_with_callback_and_timeout_and_arguments_0(cb.as_ref().unchecked_ref(), ms as i32)
+            .unwrap();
+    return defer(move || {
+        window().clear_interval_with_handle(id);
+        drop(cb);
+    });
+}
+
+type FrameCallback = Rc<RefCell<Option<Closure<dyn FnMut(f64)>>>>;
+
+/// Call `cb` before every repaint with the frame timestamp until the returned scope
+/// value is dropped, for animations and polling loops.
+pub fn animation_loop(mut cb: impl FnMut(f64) + 'static) -> ScopeValue {
+    let id = Rc::new(Cell::new(None));
+    let js_cb: FrameCallback = Rc::new(RefCell::new(None));
+    *js_cb.borrow_mut() = Some(Closure::wrap(Box::new({
+        let id = id.clone();
+        let js_cb = Rc::downgrade(&js_cb);
+        move |t: f64| {
+            cb(t);
+            let Some(js_cb) = js_cb.upgrade() else {
+                return;
+            };
+            let js_cb = js_cb.borrow();
+            let Some(js_cb) = js_cb.as_ref() else {
+                return;
+            };
+            id.set(window().request_animation_frame(js_cb.as_ref().unchecked_ref()).ok());
+        }
+    }) as Box<dyn FnMut(f64)>));
+    id.set(window().request_animation_frame(js_cb.borrow().as_ref().unwrap().as_ref().unchecked_ref()).ok());
+    return defer(move || {
+        if let Some(id) = id.get() {
+            _ = window().cancel_animation_frame(id);
+        }
+        drop(js_cb.take());
+    });
+}
+
+/// Call `cb` before the next repaint (`requestAnimationFrame`).  If the returned
+/// scope value is dropped first the callback is canceled.
+pub fn animation_frame(cb: impl FnOnce(f64) + 'static) -> ScopeValue {
+    let cb = Closure::once(cb);
+    let id = window().request_animation_frame(cb.as_ref().unchecked_ref()).unwrap();
+    return defer(move || {
+        _ = window().cancel_animation_frame(id);
+        drop(cb);
+    });
+}
+
+/// Call `cb` after the next repaint, once the current DOM changes have been laid
+/// out and painted (a zero timeout started from `requestAnimationFrame`).  Use this
+/// to measure or focus elements after making changes.  If the returned scope value
+/// is dropped first the callback is canceled.
+pub fn after_next_paint(cb: impl FnOnce() + 'static) -> ScopeValue {
+    let pending: Pending = Rc::new(RefCell::new(None));
+    let frame = animation_frame({
+        let pending = Rc::downgrade(&pending);
+        move |_| {
+            let Some(pending) = pending.upgrade() else {
+                return;
+            };
+            *pending.borrow_mut() = Some(timeout(0, cb));
+        }
+    });
+    return scope_any((frame, pending));
+}
+
+/// Call `cb` once the current task finishes, before the browser does anything else
+/// (`queueMicrotask`).  If the returned scope value is dropped first the callback
+/// is skipped.
+pub fn after_microtask(cb: impl FnOnce() + 'static) -> ScopeValue {
+    let canceled = Rc::new(Cell::new(false));
+    let cb = Closure::once_into_js({
+        let canceled = canceled.clone();
+        move || {
+            if canceled.get() {
+                return;
+            }
+            cb();
+        }
+    });
+    window().queue_microtask(cb.unchecked_ref());
+    return defer(move || {
+        canceled.set(true);
+    });
+}
+
+/// Call `cb` when the browser is idle (`requestIdleCallback`), or after a zero
+/// timeout in browsers that don't support it.  If the returned scope value is
+/// dropped first the callback is canceled.
+pub fn idle_callback(cb: impl FnOnce() + 'static) -> ScopeValue {
+    if !Reflect::has(&window(), &JsValue::from_str("requestIdleCallback")).unwrap_or(false) {
+        return timeout(0, cb);
+    }
+    let cb = Closure::once(cb);
+    let id = window().request_idle_callback(cb.as_ref().unchecked_ref()).unwrap();
+    return defer(move || {
+        window().cancel_idle_callback(id);
+        drop(cb);
+    });
+}
+
+type Pending = Rc<RefCell<Option<ScopeValue>>>;
+
+fn extend_incremental_step(
+    parent: WeakEl,
+    mut items: Box<dyn Iterator<Item = El>>,
+    chunk_size: usize,
+    pending: Weak<RefCell<Option<ScopeValue>>>,
+) {
+    let Some(pending1) = pending.upgrade() else {
+        return;
+    };
+    *pending1.borrow_mut() = Some(idle_callback(move || {
+        let Some(parent1) = parent.upgrade() else {
+            return;
+        };
+        let chunk = items.by_ref().take(chunk_size).collect::<Vec<_>>();
+        if chunk.is_empty() {
+            return;
+        }
+        parent1.ref_extend(chunk);
+        extend_incremental_step(parent, items, chunk_size, pending);
+    }));
+}
+
+/// Append the elements to `parent` in chunks of `chunk_size`, one chunk per idle
+/// callback (see `idle_callback`), so adding a large number of children doesn't
+/// block the main thread.  The iterator is consumed lazily, so elements can be
+/// built as they're added.
+///
+/// Dropping the returned value (or `parent`) cancels any remaining work.
+pub fn extend_incremental(
+    parent: &El,
+    items: impl IntoIterator<Item = El> + 'static,
+    chunk_size: usize,
+) -> ScopeValue {
+    let pending: Pending = Rc::new(RefCell::new(None));
+    extend_incremental_step(
+        parent.weak(),
+        Box::new(items.into_iter()),
+        chunk_size.max(1),
+        Rc::downgrade(&pending),
+    );
+    return scope_any(pending);
+}
@@ -0,0 +1,135 @@
+use crate::{
+    ContainerEntry,
+    El,
+};
+
+/// Like `Container`, but entries are split between several section elements (ex:
+/// pinned rows and normal rows) by a classifier.  Entries keep a single logical
+/// order, and within each section they appear in that order.
+///
+/// If you change an entry in a way that affects its classification, call
+/// `reclassify` (or use `modify`) to move it to the right section.
+pub struct SectionedContainer<T: ContainerEntry> {
+    sections: Vec<El>,
+    classify: Box<dyn Fn(&T) -> usize>,
+    entries: Vec<T>,
+    entry_sections: Vec<usize>,
+}
+
+impl<T: ContainerEntry> SectionedContainer<T> {
+    /// `classify` returns the index of the section element for an entry.  Panics
+    /// later if it returns an index outside of `sections`.
+    pub fn new(sections: Vec<El>, classify: impl Fn(&T) -> usize + 'static) -> Self {
+        return SectionedContainer {
+            sections,
+            classify: Box::new(classify),
+            entries: vec![],
+            entry_sections: vec![],
+        };
+    }
+
+    /// The section elements.
+    pub fn sections(&self) -> &[El] {
+        return &self.sections;
+    }
+
+    fn classify(&self, entry: &T) -> usize {
+        let section = (self.classify)(entry);
+        if section >= self.sections.len() {
+            panic!("Classifier returned section {} but there are only {} sections", section, self.sections.len());
+        }
+        return section;
+    }
+
+    /// The index within `section` an entry at logical index `i` would have.
+    fn section_index(&self, section: usize, i: usize) -> usize {
+        return self.entry_sections[.. i].iter().filter(|s| **s == section).count();
+    }
+
+    pub fn len(&self) -> usize {
+        return self.entries.len();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.entries.is_empty();
+    }
+
+    pub fn iter(&self) -> core::slice::Iter<'_, T> {
+        return self.entries.iter();
+    }
+
+    pub fn get(&self, i: usize) -> Option<&T> {
+        return self.entries.get(i);
+    }
+
+    /// The section the entry at `i` is in.
+    pub fn section_of(&self, i: usize) -> Option<usize> {
+        return self.entry_sections.get(i).copied();
+    }
+
+    /// Iterate the entries in a section, with their logical indexes.
+    pub fn iter_section(&self, section: usize) -> impl Iterator<Item = (usize, &T)> {
+        return self
+            .entries
+            .iter()
+            .enumerate()
+            .filter(move |(i, _)| self.entry_sections[*i] == section);
+    }
+
+    pub fn push(&mut self, entry: T) {
+        self.insert(self.entries.len(), entry);
+    }
+
+    pub fn insert(&mut self, i: usize, entry: T) {
+        let section = self.classify(&entry);
+        let section_i = self.section_index(section, i);
+        self.sections[section].ref_splice(section_i, 0, vec![entry.el().clone()]);
+        self.entries.insert(i, entry);
+        self.entry_sections.insert(i, section);
+    }
+
+    pub fn remove(&mut self, i: usize) -> T {
+        let section = self.entry_sections[i];
+        let section_i = self.section_index(section, i);
+        self.sections[section].ref_splice(section_i, 1, vec![]);
+        self.entry_sections.remove(i);
+        return self.entries.remove(i);
+    }
+
+    pub fn clear(&mut self) {
+        for section in &self.sections {
+            section.ref_clear();
+        }
+        self.entries.clear();
+        self.entry_sections.clear();
+    }
+
+    /// Modify the entry at `i`, then move it to a different section if its
+    /// classification changed.
+    pub fn modify(&mut self, i: usize, f: impl FnOnce(&mut T)) {
+        f(&mut self.entries[i]);
+        self.reclassify(i);
+    }
+
+    /// Move the entry at `i` to a different section if its classification changed.
+    pub fn reclassify(&mut self, i: usize) {
+        let old = self.entry_sections[i];
+        let new = self.classify(&self.entries[i]);
+        if old == new {
+            return;
+        }
+        let el = self.entries[i].el().clone();
+        let old_i = self.section_index(old, i);
+        let new_i = self.section_index(new, i);
+        self.sections[old].ref_splice(old_i, 1, vec![]);
+        self.sections[new].ref_splice(new_i, 0, vec![el]);
+        self.entry_sections[i] = new;
+    }
+
+    /// Reclassify all entries, see `reclassify`.
+    pub fn reclassify_all(&mut self) {
+        for i in 0 .. self.entries.len() {
+            self.reclassify(i);
+        }
+    }
+}
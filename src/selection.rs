@@ -0,0 +1,148 @@
+use gloo_events::EventListener;
+use gloo_utils::{
+    document,
+    window,
+};
+use web_sys::{
+    Element,
+    Node,
+    Range,
+    Selection,
+};
+use crate::{
+    el,
+    error::ReportExt,
+    El,
+};
+
+/// `NodeFilter.SHOW_TEXT`
+const SHOW_TEXT: u32 = 4;
+
+/// The current selection's first range, if it's within `root`.
+fn range_within(root: &Element) -> Option<(Selection, Range)> {
+    let selection = window().get_selection().ok()??;
+    if selection.range_count() == 0 {
+        return None;
+    }
+    let range = selection.get_range_at(0).ok()?;
+    if !root.contains(Some(&range.start_container().ok()?)) || !root.contains(Some(&range.end_container().ok()?)) {
+        return None;
+    }
+    return Some((selection, range));
+}
+
+/// The text offset (in UTF-16 code units, like JS string indices) of the position
+/// `node`/`offset` within `root`'s text content.
+fn text_offset(root: &Element, node: &Node, offset: u32) -> Option<u32> {
+    let range = document().create_range().ok()?;
+    range.select_node_contents(root).ok()?;
+    range.set_end(node, offset).ok()?;
+    return Some(String::from(range.to_string()).encode_utf16().count() as u32);
+}
+
+/// The text node and offset within it at text offset `offset` within `root`.  If
+/// the offset is past the end, this returns the end of the last text node.
+fn text_position(root: &Element, offset: u32) -> Option<(Node, u32)> {
+    let walker = document().create_tree_walker_with_what_to_show(root, SHOW_TEXT).ok()?;
+    let mut remaining = offset;
+    let mut last = None;
+    while let Some(node) = walker.next_node().ok()? {
+        let len = node.text_content().unwrap_or_default().encode_utf16().count() as u32;
+        if remaining <= len {
+            return Some((node, remaining));
+        }
+        remaining -= len;
+        last = Some((node, len));
+    }
+    return last;
+}
+
+/// Helpers for working with the selection and caret, for `contenteditable`
+/// elements.  Offsets are positions in the element's text content in UTF-16 code
+/// units (like JS string indices).
+impl El {
+    /// The position of the caret (the focus end of the selection) if it's within the
+    /// element.
+    pub fn caret_offset(&self) -> Option<u32> {
+        let raw = self.raw();
+        let (selection, _) = range_within(&raw)?;
+        return text_offset(&raw, &selection.focus_node()?, selection.focus_offset());
+    }
+
+    /// The selection start and end if the selection is within the element.
+    pub fn selection_offsets(&self) -> Option<(u32, u32)> {
+        let raw = self.raw();
+        let (_, range) = range_within(&raw)?;
+        return Some((
+            text_offset(&raw, &range.start_container().ok()?, range.start_offset().ok()?)?,
+            text_offset(&raw, &range.end_container().ok()?, range.end_offset().ok()?)?,
+        ));
+    }
+
+    /// Place the caret at the offset, replacing the current selection.
+    pub fn ref_set_caret_offset(&self, offset: u32) -> &Self {
+        return self.ref_set_selection_offsets(offset, offset);
+    }
+
+    /// Select the text between the offsets.
+    pub fn ref_set_selection_offsets(&self, start: u32, end: u32) -> &Self {
+        let raw = self.raw();
+        let Some(selection) = window().get_selection().or_report().flatten() else {
+            return self;
+        };
+        let Some(range) = document().create_range().or_report() else {
+            return self;
+        };
+        match (text_position(&raw, start), text_position(&raw, end)) {
+            (Some((start_node, start_offset)), Some((end_node, end_offset))) => {
+                range.set_start(&start_node, start_offset).or_report();
+                range.set_end(&end_node, end_offset).or_report();
+            },
+            _ => {
+                // No text, put the caret in the element itself
+                range.select_node_contents(&raw).or_report();
+                range.collapse_with_to_start(true);
+            },
+        }
+        selection.remove_all_ranges().or_report();
+        selection.add_range(&range).or_report();
+        return self;
+    }
+
+    /// Wrap the selected contents in a new element with the given tag, if there's a
+    /// non-empty selection within this element. The new element is returned (as a
+    /// raw element, since it's part of the editable contents rather than the scoped
+    /// tree) and the selection is updated to cover its contents.
+    pub fn ref_wrap_selection(&self, tag: &str) -> Option<Element> {
+        let (selection, range) = range_within(&self.raw())?;
+        if range.collapsed() {
+            return None;
+        }
+        let wrapper = el(tag).raw();
+        if range.surround_contents(&wrapper).is_err() {
+            // The range partially selects non-text nodes
+            let contents = range.extract_contents().ok()?;
+            wrapper.append_child(&contents).ok()?;
+            range.insert_node(&wrapper).ok()?;
+        }
+        range.select_node_contents(&wrapper).ok()?;
+        selection.remove_all_ranges().or_report();
+        selection.add_range(&range).or_report();
+        return Some(wrapper);
+    }
+
+    /// Call `cb` when the selection changes while it's within the element.
+    pub fn on_selection_change(self, cb: impl FnMut() + 'static) -> Self {
+        self.ref_on_selection_change(cb);
+        return self;
+    }
+
+    pub fn ref_on_selection_change(&self, mut cb: impl FnMut() + 'static) -> &Self {
+        let raw = self.raw();
+        return self.ref_own(|_| EventListener::new(&document(), "selectionchange", move |_| {
+            if range_within(&raw).is_some() {
+                cb();
+            }
+        }));
+    }
+}
@@ -0,0 +1,150 @@
+//! Support for holding elements in structures shared with other threads, when
+//! using wasm threads.
+//!
+//! Elements can't leave the main thread, but a `SendEl` is a `Send + Sync`
+//! handle to one: it can be stored and passed around anywhere, but the element can
+//! only be accessed on the thread that created it.  Other threads post work to
+//! that thread with `SendEl::post`.
+//!
+//! Call `start_main_thread_queue` on the main thread first to process posted
+//! work.
+use std::{
+    cell::RefCell,
+    mem,
+    sync::{
+        mpsc::{
+            channel,
+            Receiver,
+            Sender,
+        },
+        Arc,
+        OnceLock,
+    },
+    thread::{
+        self,
+        ThreadId,
+    },
+};
+use crate::{
+    schedule::interval,
+    El,
+    ScopeValue,
+};
+
+type Posted = Box<dyn FnOnce() + Send>;
+
+/// The queue's thread and sender.
+static QUEUE: OnceLock<(ThreadId, Sender<Posted>)> = OnceLock::new();
+
+thread_local!{
+    static RECV: RefCell<Option<Receiver<Posted>>> = const {
+        RefCell::new(None)
+    };
+}
+
+/// Wraps a value, asserting it's safe to send.  Only used for values that are only
+/// accessed on the thread that created them.
+struct AssertSend<T>(T);
+
+unsafe impl<T> Send for AssertSend<T> { }
+
+unsafe impl<T> Sync for AssertSend<T> { }
+
+/// Start running work posted from other threads (see `SendEl::post`), polling
+/// every `ms` milliseconds.  Call this once, on the main thread.  Posted work stops
+/// being processed when the returned value is dropped.
+pub fn start_main_thread_queue(ms: u32) -> ScopeValue {
+    QUEUE.get_or_init(|| {
+        let (send, recv) = channel::<Posted>();
+        RECV.with(|r| *r.borrow_mut() = Some(recv));
+        return (thread::current().id(), send);
+    });
+    return interval(ms, || {
+        loop {
+            let Some(f) = RECV.with(|r| r.borrow().as_ref().and_then(|r| r.try_recv().ok())) else {
+                break;
+            };
+            f();
+        }
+    });
+}
+
+/// Run `f` on `thread` via the main thread queue (see `start_main_thread_queue`),
+/// returning it if the queue hasn't been started or runs on a different thread.
+fn post_main(thread: ThreadId, f: Posted) -> Result<(), Posted> {
+    let Some((queue_thread, queue)) = QUEUE.get() else {
+        return Err(f);
+    };
+    if *queue_thread != thread {
+        return Err(f);
+    }
+    return queue.send(f).map_err(|e| e.0);
+}
+
+struct SendEl_ {
+    thread: ThreadId,
+    el: Option<AssertSend<El>>,
+}
+
+impl Drop for SendEl_ {
+    fn drop(&mut self) {
+        let Some(el) = self.el.take() else {
+            return;
+        };
+        if thread::current().id() == self.thread {
+            return;
+        }
+
+        // The element can't be dropped off its thread; drop it via the main thread
+        // queue if that's the element's thread, or leak it if there's no way to get it
+        // there.
+        if let Err(f) = post_main(self.thread, Box::new(move || {
+            drop(el);
+        })) {
+            mem::forget(f);
+        }
+    }
+}
+
+/// A `Send + Sync` handle to an element, see the module documentation.
+#[derive(Clone)]
+pub struct SendEl(Arc<SendEl_>);
+
+impl SendEl {
+    /// Wrap the element.  The element can only be accessed on the current thread,
+    /// which must be the main thread (the one running `start_main_thread_queue`) for
+    /// `post` to work from other threads.  If the handle is dropped on another thread
+    /// the element is sent back to the main thread to be dropped, or leaked if it
+    /// was created on a different thread.
+    pub fn new(el: El) -> Self {
+        return SendEl(Arc::new(SendEl_ {
+            thread: thread::current().id(),
+            el: Some(AssertSend(el)),
+        }));
+    }
+
+    /// Get the element, if called on the element's thread.
+    pub fn get(&self) -> Option<&El> {
+        if thread::current().id() != self.0.thread {
+            return None;
+        }
+        return self.0.el.as_ref().map(|e| &e.0);
+    }
+
+    /// Run `f` with the element on the element's thread - immediately if called on
+    /// that thread, otherwise via the main thread queue (see
+    /// `start_main_thread_queue`).  Returns `false` if the work couldn't be posted,
+    /// including if the element wasn't created on the main thread.
+    pub fn post(&self, f: impl FnOnce(&El) + Send + 'static) -> bool {
+        if let Some(el) = self.get() {
+            f(el);
+            return true;
+        }
+        let this = self.clone();
+        return post_main(self.0.thread, Box::new(move || {
+            if let Some(el) = this.get() {
+                f(el);
+            }
+        })).is_ok();
+    }
+}
@@ -0,0 +1,40 @@
+use serde::{
+    de::DeserializeOwned,
+    Serialize,
+};
+use wasm_bindgen::{
+    JsCast,
+    JsValue,
+};
+use web_sys::{
+    CustomEvent,
+    Event,
+};
+use crate::{
+    error::ReportExt,
+    El,
+};
+
+impl El {
+    /// Set a javascript property to a serialized value (see `ref_prop`), ex: to pass
+    /// structured data to a custom element.  Serialization errors go to the error
+    /// handler.
+    pub fn prop_serde(self, key: &str, value: &impl Serialize) -> Self {
+        self.ref_prop_serde(key, value);
+        return self;
+    }
+
+    pub fn ref_prop_serde(&self, key: &str, value: &impl Serialize) -> &Self {
+        let Some(value) = serde_wasm_bindgen::to_value(value).map_err(JsValue::from).or_report() else {
+            return self;
+        };
+        return self.ref_prop(key, value);
+    }
+}
+
+/// Deserialize the `detail` of a `CustomEvent`.  Returns an error if the event isn't
+/// a `CustomEvent` or the detail doesn't match `T`.
+pub fn event_detail<T: DeserializeOwned>(ev: &Event) -> Result<T, JsValue> {
+    let ev = ev.dyn_ref::<CustomEvent>().ok_or_else(|| JsValue::from_str("Event is not a CustomEvent"))?;
+    return Ok(serde_wasm_bindgen::from_value(ev.detail())?);
+}
@@ -0,0 +1,70 @@
+use crate::El;
+
+/// The DOM state of an element subtree (attributes, including classes, text, and
+/// child structure) captured with `El::snapshot`.  Listeners and other owned values
+/// aren't captured.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ElSnapshot {
+    pub tag: String,
+    /// Attributes in document order.
+    pub attrs: Vec<(String, String)>,
+    /// The text content, for elements without scoped children.
+    pub text: Option<String>,
+    pub children: Vec<ElSnapshot>,
+}
+
+impl El {
+    /// Capture the state of this element and its scoped descendants, see
+    /// `ElSnapshot`.
+    pub fn snapshot(&self) -> ElSnapshot {
+        let raw = self.raw();
+        let children = self.children();
+        return ElSnapshot {
+            tag: raw.tag_name().to_ascii_lowercase(),
+            attrs: raw
+                .get_attribute_names()
+                .iter()
+                .filter_map(|n| n.as_string())
+                .filter_map(|n| Some((n.clone(), raw.get_attribute(&n)?)))
+                .collect(),
+            text: if children.is_empty() {
+                raw.text_content()
+            } else {
+                None
+            },
+            children: children.iter().map(|c| c.snapshot()).collect(),
+        };
+    }
+
+    /// Apply a snapshot taken with `snapshot`, changing only attributes and text
+    /// that differ.  Children are restored pairwise by position; returns `false` if
+    /// the number of children or a tag differs anywhere in the subtree, in which case
+    /// the parts that don't line up are left as is.
+    pub fn restore(&self, snapshot: &ElSnapshot) -> bool {
+        let raw = self.raw();
+        if raw.tag_name().to_ascii_lowercase() != snapshot.tag {
+            return false;
+        }
+        for name in raw.get_attribute_names().iter().filter_map(|n| n.as_string()) {
+            if !snapshot.attrs.iter().any(|(k, _)| *k == name) {
+                self.ref_remove_attr(&name);
+            }
+        }
+        for (k, v) in &snapshot.attrs {
+            if raw.get_attribute(k).as_ref() != Some(v) {
+                self.ref_attr(k, v);
+            }
+        }
+        let children = self.children();
+        if let Some(text) = &snapshot.text {
+            if children.is_empty() && raw.text_content().as_ref() != Some(text) {
+                self.ref_text(text);
+            }
+        }
+        let mut matched = children.len() == snapshot.children.len();
+        for (child, child_snapshot) in children.iter().zip(&snapshot.children) {
+            matched = child.restore(child_snapshot) && matched;
+        }
+        return matched;
+    }
+}
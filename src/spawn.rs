@@ -1,15 +1,47 @@
+use std::{
+    cell::RefCell,
+    fmt::Debug,
+    pin::Pin,
+    rc::Rc,
+    task::{
+        Context,
+        Poll,
+    },
+};
 use futures::{
-    channel::oneshot::channel,
+    channel::{
+        oneshot::channel,
+        mpsc,
+    },
     select,
     Future,
     FutureExt,
+    Stream,
+    StreamExt,
+};
+use wasm_bindgen::{
+    JsCast,
+    JsValue,
 };
 use wasm_bindgen_futures::spawn_local;
+use web_sys::{
+    console,
+    IntersectionObserverEntry,
+};
 use crate::{
+    El,
+    IntersectionObserver,
+    error::report_error,
+    lifecycle::{
+        on_visibility_change,
+        page_visible,
+    },
     own::{
         ScopeValue,
         defer,
+        scope_any,
     },
+    schedule::interval,
 };
 
 /// Spawn a background task that's canceled when the returned scope value is
@@ -30,3 +62,200 @@ pub fn spawn_rooted(f: impl Future<Output = ()> + 'static) -> ScopeValue {
         _ = cancel_tx.send(());
     });
 }
+
+/// Like `spawn_rooted`, but calls `on_done` with the task's output when it
+/// completes. If the scope value is dropped first, `on_done` is never called.
+pub fn spawn_rooted_then<
+    T: 'static,
+>(f: impl Future<Output = T> + 'static, on_done: impl FnOnce(T) + 'static) -> ScopeValue {
+    return spawn_rooted(async move {
+        on_done(f.await);
+    });
+}
+
+/// Like `spawn_rooted` for fallible tasks. If the task fails, the error is logged
+/// to the console prefixed with `context` rather than being silently discarded.
+pub fn spawn_rooted_log<
+    E: Debug + 'static,
+>(context: &str, f: impl Future<Output = Result<(), E>> + 'static) -> ScopeValue {
+    return spawn_rooted_then_log(context, f, |_| { });
+}
+
+/// A combination of `spawn_rooted_then` and `spawn_rooted_log`: `on_ok` is called
+/// with the output if the task succeeds, otherwise the error is logged to the
+/// console prefixed with `context`.
+pub fn spawn_rooted_then_log<
+    T: 'static,
+    E: Debug + 'static,
+>(
+    context: &str,
+    f: impl Future<Output = Result<T, E>> + 'static,
+    on_ok: impl FnOnce(T) + 'static,
+) -> ScopeValue {
+    let context = context.to_string();
+    return spawn_rooted_then(f, move |res| match res {
+        Ok(v) => on_ok(v),
+        Err(e) => {
+            console::error_1(&JsValue::from_str(&format!("{}: {:?}", context, e)));
+        },
+    });
+}
+
+/// Spawn a task that calls `cb` with each item produced by the stream. The task
+/// stops when the stream ends or when the returned scope value is dropped, so
+/// attaching the scope value to an element with `.own()` subscribes the element to
+/// the stream for its lifetime.
+pub fn own_stream<T: 'static>(stream: impl Stream<Item = T> + 'static, mut cb: impl FnMut(T) + 'static) -> ScopeValue {
+    let mut stream = Box::pin(stream);
+    return spawn_rooted(async move {
+        while let Some(v) = stream.next().await {
+            cb(v);
+        }
+    });
+}
+
+/// A stream that produces a value every `ms` milliseconds, created with `ticker`.
+/// If the consumer falls behind, missed ticks are coalesced into one.
+pub struct Ticker {
+    rx: mpsc::Receiver<()>,
+    _interval: ScopeValue,
+}
+
+impl Stream for Ticker {
+    type Item = ();
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        return self.rx.poll_next_unpin(cx);
+    }
+}
+
+/// Create a stream that ticks every `ms` milliseconds. The underlying interval is
+/// cleared when the stream is dropped.
+pub fn ticker(ms: u32) -> Ticker {
+    let (mut tx, rx) = mpsc::channel(1);
+    return Ticker {
+        rx,
+        _interval: interval(ms, move || {
+            _ = tx.try_send(());
+        }),
+    };
+}
+
+/// Show `placeholder` until the future produces the real element, then replace the
+/// placeholder with it (see `El::ref_replace`).  If the placeholder is dropped
+/// first, the future is canceled.
+///
+/// The placeholder needs to have been added to a parent `El` by the time the
+/// future completes for the replacement to happen, otherwise the error goes to the
+/// error handler (see `set_error_handler`) and the loaded element is dropped.
+pub fn el_async(placeholder: El, f: impl Future<Output = El> + 'static) -> El {
+    return placeholder.own(|e| {
+        let e = e.weak();
+        return spawn_rooted(async move {
+            let loaded = f.await;
+            let Some(e) = e.upgrade() else {
+                return;
+            };
+            if e.ref_replace(vec![loaded]).is_none() {
+                report_error(JsValue::from_str("Async element placeholder has no parent El to be replaced in"));
+            }
+        });
+    });
+}
+
+type TaskFactory = Box<dyn FnMut() -> ScopeValue>;
+
+/// A task that should only run while some conditions hold.
+struct VisibleTask {
+    factory: TaskFactory,
+    page_visible: bool,
+    el_visible: bool,
+    task: Option<ScopeValue>,
+}
+
+fn update_visible_task(state: &Rc<RefCell<VisibleTask>>) {
+    let mut s = state.borrow_mut();
+    let run = s.page_visible && s.el_visible;
+    let stopped = if run && s.task.is_none() {
+        let task = (s.factory)();
+        s.task = Some(task);
+        None
+    } else if !run {
+        s.task.take()
+    } else {
+        None
+    };
+    drop(s);
+
+    // Drop outside the borrow in case the task's cleanup interacts with the state
+    drop(stopped);
+}
+
+fn spawn_visible_task(
+    el_visible: bool,
+    mut fut_factory: impl FnMut() -> Pin<Box<dyn Future<Output = ()>>> + 'static,
+) -> Rc<RefCell<VisibleTask>> {
+    let state = Rc::new(RefCell::new(VisibleTask {
+        factory: Box::new(move || spawn_rooted(fut_factory())),
+        page_visible: page_visible(),
+        el_visible,
+        task: None,
+    }));
+    update_visible_task(&state);
+    return state;
+}
+
+fn watch_page_visible(state: &Rc<RefCell<VisibleTask>>) -> ScopeValue {
+    let state = Rc::downgrade(state);
+    return on_visibility_change(move |visible| {
+        let Some(state) = state.upgrade() else {
+            return;
+        };
+        state.borrow_mut().page_visible = visible;
+        update_visible_task(&state);
+    });
+}
+
+/// Like `spawn_rooted` but the task only runs while the page is visible.  When the
+/// page is hidden (ex: the tab is in the background) the task is canceled, and when
+/// it becomes visible again a new task is started with `fut_factory`.
+pub fn spawn_rooted_visible<
+    F: Future<Output = ()> + 'static,
+>(mut fut_factory: impl FnMut() -> F + 'static) -> ScopeValue {
+    let state = spawn_visible_task(true, move || Box::pin(fut_factory()));
+    let listener = watch_page_visible(&state);
+    return scope_any((listener, state));
+}
+
+impl El {
+    /// Like `spawn_rooted_visible` but the task also only runs while the element is
+    /// on screen (intersecting the viewport).  The task is owned by the element.
+    pub fn spawn_visible<F: Future<Output = ()> + 'static>(self, fut_factory: impl FnMut() -> F + 'static) -> Self {
+        self.ref_spawn_visible(fut_factory);
+        return self;
+    }
+
+    pub fn ref_spawn_visible<
+        F: Future<Output = ()> + 'static,
+    >(&self, mut fut_factory: impl FnMut() -> F + 'static) -> &Self {
+        return self.ref_own(|e| {
+            let state = spawn_visible_task(false, move || Box::pin(fut_factory()));
+            let listener = watch_page_visible(&state);
+            let intersection_observer = IntersectionObserver::new({
+                let state = Rc::downgrade(&state);
+                move |entries| {
+                    let Some(state) = state.upgrade() else {
+                        return;
+                    };
+                    let Some(entry) = entries.iter().last() else {
+                        return;
+                    };
+                    state.borrow_mut().el_visible = entry.unchecked_into::<IntersectionObserverEntry>().is_intersecting();
+                    update_visible_task(&state);
+                }
+            });
+            let handle = intersection_observer.observe(&e.raw());
+            return (handle, intersection_observer, listener, state);
+        });
+    }
+}
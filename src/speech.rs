@@ -0,0 +1,240 @@
+use std::{
+    cell::{
+        Cell,
+        RefCell,
+    },
+    rc::Rc,
+};
+use gloo_events::EventListener;
+use gloo_utils::window;
+use js_sys::{
+    Array,
+    Function,
+    Reflect,
+};
+use wasm_bindgen::{
+    JsCast,
+    JsValue,
+};
+use web_sys::{
+    Event,
+    SpeechRecognition,
+    SpeechRecognitionError,
+    SpeechRecognitionEvent,
+    SpeechSynthesisErrorEvent,
+    SpeechSynthesisEvent,
+    SpeechSynthesisUtterance,
+};
+use crate::{
+    defer,
+    ScopeValue,
+};
+
+/// Voice settings for `speak`.  `None` uses the browser default.
+#[derive(Clone, Debug, Default)]
+pub struct SpeakOptions {
+    pub lang: Option<String>,
+    /// 0.1 to 10, 1 is normal
+    pub rate: Option<f32>,
+    /// 0 to 2, 1 is normal
+    pub pitch: Option<f32>,
+    /// 0 to 1
+    pub volume: Option<f32>,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum SpeakEvent {
+    Start,
+    /// Reached a word or sentence boundary at this character index of the text.
+    Boundary(u32),
+    End,
+    Error(String),
+}
+
+/// An utterance whose `speak` guard was dropped while it was waiting in the queue,
+/// canceled when its turn comes.
+struct Skipped {
+    finished: Rc<Cell<bool>>,
+    _listeners: [EventListener; 3],
+}
+
+thread_local!{
+    static SKIPPED: RefCell<Vec<Skipped>> = const {
+        RefCell::new(vec![])
+    };
+}
+
+/// Cancel the utterance when it starts.
+fn skip(utterance: &SpeechSynthesisUtterance) {
+    let finished = Rc::new(Cell::new(false));
+    let listener = |event: &'static str, cancel: bool| {
+        let finished = finished.clone();
+        return EventListener::new(utterance, event, move |_| {
+            if finished.replace(true) {
+                return;
+            }
+            if cancel {
+                if let Ok(synth) = window().speech_synthesis() {
+                    synth.cancel();
+                }
+            }
+        });
+    };
+    let skipped = Skipped {
+        _listeners: [listener("start", true), listener("end", false), listener("error", false)],
+        finished,
+    };
+    SKIPPED.with(|s| {
+        let mut s = s.borrow_mut();
+        s.retain(|s| !s.finished.get());
+        s.push(skipped);
+    });
+}
+
+/// Read `text` aloud with speech synthesis, calling `cb` as it progresses.
+///
+/// If the returned value is dropped before speaking finishes, the utterance is
+/// canceled.  Other utterances currently speaking aren't interrupted, but since the
+/// browser can only cancel its whole speech queue, utterances queued after this
+/// one are canceled too.
+pub fn speak(text: &str, options: SpeakOptions, cb: impl FnMut(SpeakEvent) + 'static) -> Result<ScopeValue, JsValue> {
+    let synth = window().speech_synthesis()?;
+    let utterance = SpeechSynthesisUtterance::new_with_text(text)?;
+    if let Some(lang) = &options.lang {
+        utterance.set_lang(lang);
+    }
+    if let Some(rate) = options.rate {
+        utterance.set_rate(rate);
+    }
+    if let Some(pitch) = options.pitch {
+        utterance.set_pitch(pitch);
+    }
+    if let Some(volume) = options.volume {
+        utterance.set_volume(volume);
+    }
+    let cb = Rc::new(RefCell::new(cb));
+    let started = Rc::new(Cell::new(false));
+    let done = Rc::new(Cell::new(false));
+    let listener = |event: &'static str, f: fn(&Event) -> SpeakEvent| {
+        let cb = cb.clone();
+        let started = started.clone();
+        let done = done.clone();
+        return EventListener::new(&utterance, event, move |ev| {
+            let ev = f(ev);
+            match ev {
+                SpeakEvent::Start => started.set(true),
+                SpeakEvent::End | SpeakEvent::Error(_) => done.set(true),
+                SpeakEvent::Boundary(_) => { },
+            }
+            (cb.borrow_mut())(ev);
+        });
+    };
+    let listeners = [
+        listener("start", |_| SpeakEvent::Start),
+        listener("boundary", |ev| SpeakEvent::Boundary(ev.unchecked_ref::<SpeechSynthesisEvent>().char_index())),
+        listener("end", |_| SpeakEvent::End),
+        listener(
+            "error",
+            |ev| SpeakEvent::Error(format!("{:?}", ev.unchecked_ref::<SpeechSynthesisErrorEvent>().error())),
+        ),
+    ];
+    synth.speak(&utterance);
+    return Ok(defer(move || {
+        drop(listeners);
+        if done.get() {
+            return;
+        }
+        if started.get() {
+            // This is the utterance currently speaking
+            synth.cancel();
+        } else {
+            skip(&utterance);
+        }
+    }));
+}
+
+/// Settings for `recognize_speech`.
+#[derive(Clone, Debug, Default)]
+pub struct RecognizeOptions {
+    pub lang: Option<String>,
+    /// Keep listening after the first result.
+    pub continuous: bool,
+    /// Also report in-progress (non-final) results.
+    pub interim_results: bool,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum RecognizeEvent {
+    Result {
+        transcript: String,
+        confidence: f32,
+        is_final: bool,
+    },
+    /// Recognition stopped (ex: the user stopped speaking, unless `continuous`).
+    End,
+    Error(String),
+}
+
+fn new_recognition() -> Result<SpeechRecognition, JsValue> {
+    // Chrome only provides the prefixed version
+    for name in ["SpeechRecognition", "webkitSpeechRecognition"] {
+        let constructor = Reflect::get(&window(), &JsValue::from_str(name))?;
+        if let Some(constructor) = constructor.dyn_ref::<Function>() {
+            return Ok(Reflect::construct(constructor, &Array::new())?.unchecked_into());
+        }
+    }
+    return Err(JsValue::from_str("Speech recognition is not supported"));
+}
+
+/// Start listening to the microphone and transcribing speech, calling `cb` with
+/// results.  Recognition is aborted when the returned value is dropped.
+pub fn recognize_speech(
+    options: RecognizeOptions,
+    cb: impl FnMut(RecognizeEvent) + 'static,
+) -> Result<ScopeValue, JsValue> {
+    let recognition = new_recognition()?;
+    if let Some(lang) = &options.lang {
+        recognition.set_lang(lang);
+    }
+    recognition.set_continuous(options.continuous)?;
+    recognition.set_interim_results(options.interim_results);
+    let cb = Rc::new(RefCell::new(cb));
+    let listeners = [EventListener::new(&recognition, "result", {
+        let cb = cb.clone();
+        move |ev| {
+            let ev = ev.unchecked_ref::<SpeechRecognitionEvent>();
+            let Some(results) = ev.results() else {
+                return;
+            };
+            for i in ev.result_index() .. results.length() {
+                let Some(result) = results.get(i) else {
+                    continue;
+                };
+                let Some(alt) = result.get(0) else {
+                    continue;
+                };
+                (cb.borrow_mut())(RecognizeEvent::Result {
+                    transcript: alt.transcript(),
+                    confidence: alt.confidence(),
+                    is_final: result.is_final(),
+                });
+            }
+        }
+    }), EventListener::new(&recognition, "end", {
+        let cb = cb.clone();
+        move |_| {
+            (cb.borrow_mut())(RecognizeEvent::End);
+        }
+    }), EventListener::new(&recognition, "error", {
+        let cb = cb.clone();
+        move |ev| {
+            let ev = ev.unchecked_ref::<SpeechRecognitionError>();
+            (cb.borrow_mut())(RecognizeEvent::Error(ev.message().unwrap_or_else(|| format!("{:?}", ev.error()))));
+        }
+    })];
+    recognition.start()?;
+    return Ok(defer(move || {
+        drop(listeners);
+        recognition.abort();
+    }));
+}
@@ -0,0 +1,186 @@
+use std::{
+    cell::{
+        Cell,
+        RefCell,
+    },
+    rc::Rc,
+};
+use crate::{
+    el,
+    style,
+    ContainerEntry,
+    Display,
+    DragGesture,
+    El,
+};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SplitOrientation {
+    /// Panes side by side, the handle drags left and right.
+    Horizontal,
+    /// Panes stacked, the handle drags up and down.
+    Vertical,
+}
+
+type SplitChange = Box<dyn FnMut(f64)>;
+
+struct SplitState {
+    orientation: SplitOrientation,
+    first: El,
+    size: Cell<f64>,
+    min: Cell<f64>,
+    max: Cell<f64>,
+    #[cfg(feature = "storage")]
+    storage_key: RefCell<Option<String>>,
+    drag_start: Cell<f64>,
+    on_change: RefCell<Vec<SplitChange>>,
+}
+
+impl SplitState {
+    fn set_size(&self, size: f64) {
+        let size = size.min(self.max.get()).max(self.min.get());
+        self.size.set(size);
+        self.first.ref_style(style().prop("flex", &format!("0 0 {}px", size)));
+    }
+}
+
+/// Two panes separated by a draggable handle that resizes them.  The size is that
+/// of the first pane along the split direction; the second pane takes the
+/// remaining space.
+///
+/// The handle has the class `rooting-split-handle` for styling (ex: its width and
+/// cursor).  The pane keeps working if the `SplitPane` is dropped after its element
+/// is placed in the tree.
+pub struct SplitPane {
+    el: El,
+    handle: El,
+    state: Rc<SplitState>,
+}
+
+impl SplitPane {
+    pub fn new(orientation: SplitOrientation, first: El, second: El) -> SplitPane {
+        let handle = el("div").classes(&["rooting-split-handle"]).attr("role", "separator").attr(
+            "aria-orientation",
+            match orientation {
+                SplitOrientation::Horizontal => "vertical",
+                SplitOrientation::Vertical => "horizontal",
+            },
+        ).style(style().prop("flex", "0 0 auto").prop("touch-action", "none"));
+        second.ref_style(style().prop("flex", "1 1 0").prop("min-width", "0").prop("min-height", "0"));
+        let state = Rc::new(SplitState {
+            orientation,
+            first: first.clone(),
+            size: Cell::new(0.),
+            min: Cell::new(0.),
+            max: Cell::new(f64::INFINITY),
+            #[cfg(feature = "storage")]
+            storage_key: RefCell::new(None),
+            drag_start: Cell::new(0.),
+            on_change: RefCell::new(vec![]),
+        });
+        handle.ref_on_drag_gesture({
+            let state = Rc::downgrade(&state);
+            move |g| {
+                let Some(state) = state.upgrade() else {
+                    return;
+                };
+                let along = |dx: f64, dy: f64| match state.orientation {
+                    SplitOrientation::Horizontal => dx,
+                    SplitOrientation::Vertical => dy,
+                };
+                match g {
+                    DragGesture::Start { .. } => {
+                        let bounds = state.first.ref_bounds();
+                        state.drag_start.set(along(bounds.width, bounds.height));
+                    },
+                    DragGesture::Move { dx, dy, .. } => {
+                        state.set_size(state.drag_start.get() + along(dx, dy));
+                    },
+                    DragGesture::End { dx, dy, .. } => {
+                        state.set_size(state.drag_start.get() + along(dx, dy));
+                        let size = state.size.get();
+                        #[cfg(feature = "storage")]
+                        if let Some(key) = state.storage_key.borrow().as_ref() {
+                            crate::storage::store_f64(key, size);
+                        }
+                        for cb in state.on_change.borrow_mut().iter_mut() {
+                            cb(size);
+                        }
+                    },
+                    DragGesture::Cancel => { },
+                }
+            }
+        });
+        let el = el("div").style(style().display(Display::Flex).prop("flex-direction", match orientation {
+            SplitOrientation::Horizontal => "row",
+            SplitOrientation::Vertical => "column",
+        })).extend(vec![first, handle.clone(), second]).own(|_| state.clone());
+        return SplitPane {
+            el,
+            handle,
+            state,
+        };
+    }
+
+    /// The smallest size the first pane can be dragged to, in pixels.
+    pub fn min_size(self, px: f64) -> Self {
+        self.state.min.set(px);
+        return self;
+    }
+
+    /// The largest size the first pane can be dragged to, in pixels.
+    pub fn max_size(self, px: f64) -> Self {
+        self.state.max.set(px);
+        return self;
+    }
+
+    /// Restore the size from `localStorage` under `key` if previously saved, and
+    /// save it there after each drag.
+    #[cfg(feature = "storage")]
+    pub fn persist(self, key: &str) -> Self {
+        if let Some(size) = crate::storage::load_f64(key) {
+            self.state.set_size(size);
+        }
+        *self.state.storage_key.borrow_mut() = Some(key.to_string());
+        return self;
+    }
+
+    /// The size of the first pane in pixels, as last set.
+    pub fn size(&self) -> f64 {
+        return self.state.size.get();
+    }
+
+    /// Set the size of the first pane in pixels, limited by the min and max.
+    pub fn set_size(&self, px: f64) {
+        self.state.set_size(px);
+    }
+
+    pub fn handle(&self) -> &El {
+        return &self.handle;
+    }
+
+    /// Call `cb` with the new size when the user finishes dragging the handle.
+    pub fn on_change(&self, cb: impl FnMut(f64) + 'static) -> &Self {
+        self.state.on_change.borrow_mut().push(Box::new(cb));
+        return self;
+    }
+
+    /// Call `cb` with the first pane's size along the split whenever it's laid out
+    /// at a different size (ex: from dragging, or the window resizing).
+    pub fn on_resize(&self, cb: impl Fn(f64) + 'static) -> &Self {
+        let orientation = self.state.orientation;
+        self.state.first.ref_on_resize(move |_, inline, block| {
+            cb(match orientation {
+                SplitOrientation::Horizontal => inline,
+                SplitOrientation::Vertical => block,
+            });
+        });
+        return self;
+    }
+}
+
+impl ContainerEntry for SplitPane {
+    fn el(&self) -> &El {
+        return &self.el;
+    }
+}
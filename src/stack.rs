@@ -0,0 +1,115 @@
+use crate::{
+    ContainerEntry,
+    El,
+};
+
+/// What happens to a pane's element when another pane is made active.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum StackPolicy {
+    /// The element is detached from the DOM but kept alive, along with anything it
+    /// owns (listeners, tasks), and reattached as-is when it's activated again.
+    KeepAlive,
+    /// The element is dropped and rebuilt from scratch when it's activated again.
+    Drop,
+}
+
+struct StackPane {
+    build: Box<dyn Fn() -> El>,
+    el: Option<El>,
+}
+
+type StackChange = Box<dyn FnMut(Option<usize>, usize)>;
+
+/// Owns a number of panes (built lazily) but only keeps the active one attached to
+/// the DOM, like the body of a tab view.
+pub struct Stack {
+    el: El,
+    policy: StackPolicy,
+    panes: Vec<StackPane>,
+    active: Option<usize>,
+    on_change: Vec<StackChange>,
+}
+
+impl Stack {
+    /// `el` is the element the active pane is placed in.
+    pub fn new(el: El, policy: StackPolicy) -> Self {
+        return Stack {
+            el,
+            policy,
+            panes: vec![],
+            active: None,
+            on_change: vec![],
+        };
+    }
+
+    /// Add a pane.  `build` is called the first time the pane is activated (and again
+    /// each time with `StackPolicy::Drop`).  Returns the pane's index.
+    pub fn push(&mut self, build: impl Fn() -> El + 'static) -> usize {
+        self.panes.push(StackPane {
+            build: Box::new(build),
+            el: None,
+        });
+        return self.panes.len() - 1;
+    }
+
+    pub fn len(&self) -> usize {
+        return self.panes.len();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.panes.is_empty();
+    }
+
+    pub fn active(&self) -> Option<usize> {
+        return self.active;
+    }
+
+    /// The element of the pane, if it's currently built.
+    pub fn pane_el(&self, i: usize) -> Option<&El> {
+        return self.panes.get(i)?.el.as_ref();
+    }
+
+    /// Detach the current pane and attach pane `i`, building it if necessary.  Does
+    /// nothing if `i` is already active or out of range.
+    pub fn set_active(&mut self, i: usize) {
+        if self.active == Some(i) || i >= self.panes.len() {
+            return;
+        }
+        let previous = self.active;
+
+        // Only remove the previous pane, leaving any other children in place
+        let mut at = None;
+        if let Some(previous) = previous {
+            if let Some(previous_el) = &self.panes[previous].el {
+                at = self.el.ref_index_of(previous_el);
+                self.el.ref_remove_child(previous_el);
+            }
+            if self.policy == StackPolicy::Drop {
+                self.panes[previous].el = None;
+            }
+        }
+        let pane = &mut self.panes[i];
+        let pane_el = pane.el.get_or_insert_with(|| (pane.build)()).clone();
+        match at {
+            Some(at) => self.el.ref_splice(at, 0, vec![pane_el]),
+            None => self.el.ref_push(pane_el),
+        };
+        self.active = Some(i);
+        for cb in &mut self.on_change {
+            cb(previous, i);
+        }
+    }
+
+    /// Call `cb` with the previous and new active pane indexes when the active pane
+    /// changes.
+    pub fn on_change(&mut self, cb: impl FnMut(Option<usize>, usize) + 'static) -> &mut Self {
+        self.on_change.push(Box::new(cb));
+        return self;
+    }
+}
+
+impl ContainerEntry for Stack {
+    fn el(&self) -> &El {
+        return &self.el;
+    }
+}
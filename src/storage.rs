@@ -0,0 +1,19 @@
+use gloo_utils::window;
+use web_sys::Storage;
+
+/// `localStorage`, if available (it can be disabled by the browser).
+pub fn local_storage() -> Option<Storage> {
+    return window().local_storage().ok().flatten();
+}
+
+#[cfg(feature = "storage")]
+pub(crate) fn load_f64(key: &str) -> Option<f64> {
+    return local_storage()?.get_item(key).ok()??.parse().ok();
+}
+
+#[cfg(feature = "storage")]
+pub(crate) fn store_f64(key: &str, value: f64) {
+    if let Some(s) = local_storage() {
+        _ = s.set_item(key, &value.to_string());
+    }
+}
@@ -0,0 +1,246 @@
+use std::{
+    borrow::Cow,
+    fmt::Write,
+};
+use gloo_utils::document;
+use js_sys::Reflect;
+use wasm_bindgen::{
+    JsCast,
+    JsValue,
+};
+use web_sys::{
+    CssStyleDeclaration,
+    Element,
+};
+use crate::{
+    error::{
+        report_error,
+        ReportExt,
+    },
+    El,
+};
+
+/// Values for `display`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Display {
+    None,
+    Block,
+    Inline,
+    InlineBlock,
+    Flex,
+    InlineFlex,
+    Grid,
+    InlineGrid,
+    Contents,
+}
+
+impl Display {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Display::None => return "none",
+            Display::Block => return "block",
+            Display::Inline => return "inline",
+            Display::InlineBlock => return "inline-block",
+            Display::Flex => return "flex",
+            Display::InlineFlex => return "inline-flex",
+            Display::Grid => return "grid",
+            Display::InlineGrid => return "inline-grid",
+            Display::Contents => return "contents",
+        }
+    }
+}
+
+/// Values for `position`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Position {
+    Static,
+    Relative,
+    Absolute,
+    Fixed,
+    Sticky,
+}
+
+impl Position {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Position::Static => return "static",
+            Position::Relative => return "relative",
+            Position::Absolute => return "absolute",
+            Position::Fixed => return "fixed",
+            Position::Sticky => return "sticky",
+        }
+    }
+}
+
+/// A set of inline style properties, built with typed setters and applied with
+/// `El::style`.  Properties not in the set are left untouched when applying.
+#[derive(Clone, Default, Debug)]
+pub struct Style {
+    props: Vec<(Cow<'static, str>, String)>,
+}
+
+/// Start building a `Style`.
+pub fn style() -> Style {
+    return Style::default();
+}
+
+macro_rules! px_props{
+    ($($method: ident => $prop: literal),* $(,)?) => {
+        $(
+            pub fn $method(self, v: f64) -> Self {
+                return self.set($prop, format!("{}px", v));
+            }
+        )*
+    };
+}
+
+impl Style {
+    fn set(self, prop: &'static str, value: String) -> Self {
+        return self.set_cow(Cow::Borrowed(prop), value);
+    }
+
+    fn set_cow(mut self, prop: Cow<'static, str>, value: String) -> Self {
+        self.props.retain(|(p, _)| *p != prop);
+        self.props.push((prop, value));
+        return self;
+    }
+
+    px_props!{
+        width_px => "width",
+        height_px => "height",
+        min_width_px => "min-width",
+        min_height_px => "min-height",
+        max_width_px => "max-width",
+        max_height_px => "max-height",
+        left_px => "left",
+        top_px => "top",
+        right_px => "right",
+        bottom_px => "bottom",
+        gap_px => "gap",
+    }
+
+    pub fn display(self, v: Display) -> Self {
+        return self.set("display", v.as_str().to_string());
+    }
+
+    pub fn position(self, v: Position) -> Self {
+        return self.set("position", v.as_str().to_string());
+    }
+
+    /// Between 0 and 1.
+    pub fn opacity(self, v: f64) -> Self {
+        return self.set("opacity", v.to_string());
+    }
+
+    pub fn z_index(self, v: i32) -> Self {
+        return self.set("z-index", v.to_string());
+    }
+
+    /// `transform: translate(xpx, ypx)`.  Replaces any other transform in this style.
+    pub fn transform_translate(self, x: f64, y: f64) -> Self {
+        return self.set("transform", format!("translate({}px, {}px)", x, y));
+    }
+
+    /// `transform: scale(v)`.  Replaces any other transform in this style.
+    pub fn transform_scale(self, v: f64) -> Self {
+        return self.set("transform", format!("scale({})", v));
+    }
+
+    /// Set any property, for cases not covered by the typed setters.
+    pub fn prop(self, prop: &'static str, value: &str) -> Self {
+        return self.set(prop, value.to_string());
+    }
+
+    fn prop_owned(self, prop: String, value: &str) -> Self {
+        return self.set_cow(Cow::Owned(prop), value.to_string());
+    }
+
+    /// The properties as `cssText`, ex: for a `style` attribute.
+    pub fn to_css_text(&self) -> String {
+        let mut out = String::new();
+        for (prop, value) in &self.props {
+            _ = write!(out, "{}: {};", prop, value);
+        }
+        return out;
+    }
+}
+
+fn var_name(name: &str) -> String {
+    if name.starts_with("--") {
+        return name.to_string();
+    }
+    return format!("--{}", name);
+}
+
+/// The inline style of any element that has one (html, svg, and mathml elements).
+fn inline_style(e: &Element) -> Option<CssStyleDeclaration> {
+    return Reflect::get(e, &JsValue::from_str("style")).ok()?.dyn_into::<CssStyleDeclaration>().ok();
+}
+
+/// Set a css custom property on the document's root element, for values used
+/// throughout the page (ex: theme colors).  `name` may be given with or without the
+/// leading `--`.
+pub fn set_global_css_var(name: &str, value: &str) {
+    let Some(root) = document().document_element() else {
+        return;
+    };
+    let Some(style) = inline_style(&root) else {
+        return;
+    };
+    style.set_property(&var_name(name), value).or_report();
+}
+
+pub fn remove_global_css_var(name: &str) {
+    let Some(root) = document().document_element() else {
+        return;
+    };
+    let Some(style) = inline_style(&root) else {
+        return;
+    };
+    style.remove_property(&var_name(name)).or_report();
+}
+
+impl El {
+    fn write_style(&self, f: impl FnOnce(&CssStyleDeclaration) + 'static) {
+        let e = self.raw();
+        let Some(style) = inline_style(&e) else {
+            report_error(JsValue::from_str("Element doesn't support inline style"));
+            return;
+        };
+        self.write(move |_| f(&style));
+    }
+
+    /// Set a css custom property on the element without touching other inline styles.
+    /// `name` may be given with or without the leading `--`.
+    pub fn css_var(self, name: &str, value: &str) -> Self {
+        self.ref_css_var(name, value);
+        return self;
+    }
+
+    pub fn ref_css_var(&self, name: &str, value: &str) -> &Self {
+        return self.ref_style(style().prop_owned(var_name(name), value));
+    }
+
+    pub fn ref_remove_css_var(&self, name: &str) -> &Self {
+        let name = var_name(name);
+        self.write_style(move |s| {
+            s.remove_property(&name).or_report();
+        });
+        return self;
+    }
+
+    /// Set the inline style properties in `style`, leaving others as they are.
+    pub fn style(self, style: Style) -> Self {
+        self.ref_style(style);
+        return self;
+    }
+
+    pub fn ref_style(&self, style: Style) -> &Self {
+        self.write_style(move |s| {
+            for (prop, value) in style.props {
+                s.set_property(&prop, &value).or_report();
+            }
+        });
+        return self;
+    }
+}
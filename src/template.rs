@@ -0,0 +1,62 @@
+use wasm_bindgen::JsCast;
+use web_sys::{
+    DocumentFragment,
+    Element,
+    HtmlTemplateElement,
+};
+use crate::{
+    current_document,
+    el_from_raw,
+    error::ReportExt,
+    style,
+    Display,
+    El,
+};
+
+/// Html parsed once, for stamping out copies with `instantiate`.  Cloning parsed
+/// html is much faster than building large static structures element by element.
+///
+/// Mark nodes you need to access in copies with a `data-slot` attribute, and use
+/// `El::ref_slot`/`El::ref_slot_el` to get them.
+pub struct Template(HtmlTemplateElement);
+
+/// Parse `html` into a `Template`.  Since `html` is parsed as html it must only
+/// come from trusted sources.
+pub fn template_el(html: &str) -> Template {
+    let template = current_document().create_element("template").unwrap().dyn_into::<HtmlTemplateElement>().unwrap();
+    template.set_inner_html(html.trim());
+    return Template(template);
+}
+
+impl Template {
+    /// Make a copy of the parsed html.  If the html has a single root element it's
+    /// returned, otherwise the roots are wrapped in a `div` with `display: contents`.
+    pub fn instantiate(&self) -> El {
+        let content = self.0.content().clone_node_with_deep(true).unwrap();
+        let content = content.dyn_into::<DocumentFragment>().unwrap();
+        if content.child_element_count() == 1 && content.child_nodes().length() == 1 {
+            return el_from_raw(content.first_element_child().unwrap());
+        }
+        let wrapper = el_from_raw(current_document().create_element("div").unwrap()).style(style().display(Display::Contents));
+        wrapper.raw().append_child(&content).or_report();
+        return wrapper;
+    }
+}
+
+impl El {
+    /// Find the element with `data-slot="name"` inside this element (ex: in a copy
+    /// of a `Template`).
+    pub fn ref_slot(&self, name: &str) -> Option<Element> {
+        return self.raw().query_selector(&format!("[data-slot=\"{}\"]", name)).or_report()?;
+    }
+
+    /// Like `ref_slot`, but wraps the slot in an `El` for attaching listeners and
+    /// other values.  The wrapper is owned by this element, so anything it owns lives
+    /// as long as this element.  The wrapper isn't a scoped child, so don't add or
+    /// remove its children with `El` methods unless it has no other content.
+    pub fn ref_slot_el(&self, name: &str) -> Option<El> {
+        let slot = el_from_raw(self.ref_slot(name)?);
+        self.ref_own(|_| slot.clone());
+        return Some(slot);
+    }
+}
@@ -0,0 +1,151 @@
+use std::{
+    cell::{
+        Cell,
+        RefCell,
+    },
+    rc::Rc,
+};
+use gloo_events::EventListener;
+use gloo_utils::{
+    document,
+    window,
+};
+use web_sys::MediaQueryList;
+use crate::{
+    storage::local_storage,
+    error::ReportExt,
+    Listeners,
+    ScopeValue,
+};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Theme {
+    Light,
+    Dark,
+}
+
+impl Theme {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Theme::Light => return "light",
+            Theme::Dark => return "dark",
+        }
+    }
+
+    fn parse(s: &str) -> Option<Theme> {
+        match s {
+            "light" => return Some(Theme::Light),
+            "dark" => return Some(Theme::Dark),
+            _ => return None,
+        }
+    }
+}
+
+struct ThemeManager_ {
+    storage_key: String,
+    query: Option<MediaQueryList>,
+    system: Cell<Theme>,
+    override_: Cell<Option<Theme>>,
+    listeners: Listeners<Theme>,
+    query_listener: RefCell<Option<EventListener>>,
+}
+
+impl ThemeManager_ {
+    fn theme(&self) -> Theme {
+        return self.override_.get().unwrap_or(self.system.get());
+    }
+
+    fn apply(&self) {
+        let theme = self.theme();
+        if let Some(root) = document().document_element() {
+            root.set_attribute("data-theme", theme.as_str()).or_report();
+        }
+        self.listeners.emit(&theme);
+    }
+}
+
+/// Tracks the light/dark theme: the system preference (`prefers-color-scheme`) unless
+/// overridden by the user, with the override persisted to `localStorage`.  The
+/// current theme is set as the `data-theme` attribute (`light` or `dark`) on the
+/// document's root element for use in css.
+///
+/// The manager is clonable; the system preference is tracked until the last clone
+/// is dropped.
+#[derive(Clone)]
+pub struct ThemeManager(Rc<ThemeManager_>);
+
+impl ThemeManager {
+    /// `storage_key` is the `localStorage` key the override is stored under.
+    pub fn new(storage_key: &str) -> ThemeManager {
+        let query = window().match_media("(prefers-color-scheme: dark)").ok().flatten();
+        let override_ =
+            local_storage()
+                .and_then(|s| s.get_item(storage_key).ok().flatten())
+                .and_then(|v| Theme::parse(&v));
+        let inner = Rc::new(ThemeManager_ {
+            storage_key: storage_key.to_string(),
+            system: Cell::new(if query.as_ref().is_some_and(|q| q.matches()) {
+                Theme::Dark
+            } else {
+                Theme::Light
+            }),
+            query,
+            override_: Cell::new(override_),
+            listeners: Listeners::new(),
+            query_listener: RefCell::new(None),
+        });
+        if let Some(query) = &inner.query {
+            *inner.query_listener.borrow_mut() = Some(EventListener::new(query, "change", {
+                let inner = Rc::downgrade(&inner);
+                move |_| {
+                    let Some(inner) = inner.upgrade() else {
+                        return;
+                    };
+                    let Some(query) = &inner.query else {
+                        return;
+                    };
+                    inner.system.set(if query.matches() {
+                        Theme::Dark
+                    } else {
+                        Theme::Light
+                    });
+                    inner.apply();
+                }
+            }));
+        }
+        inner.apply();
+        return ThemeManager(inner);
+    }
+
+    /// The theme in effect.
+    pub fn theme(&self) -> Theme {
+        return self.0.theme();
+    }
+
+    /// The theme the system prefers, ignoring any override.
+    pub fn system_theme(&self) -> Theme {
+        return self.0.system.get();
+    }
+
+    pub fn override_theme(&self) -> Option<Theme> {
+        return self.0.override_.get();
+    }
+
+    /// Set or clear (follow the system preference) the user's choice of theme.
+    pub fn set_override(&self, theme: Option<Theme>) {
+        self.0.override_.set(theme);
+        if let Some(s) = local_storage() {
+            match theme {
+                Some(theme) => s.set_item(&self.0.storage_key, theme.as_str()).or_report(),
+                None => s.remove_item(&self.0.storage_key).or_report(),
+            };
+        }
+        self.0.apply();
+    }
+
+    /// Call `cb` with the theme in effect whenever it may have changed, until the
+    /// returned value is dropped.
+    pub fn subscribe(&self, cb: impl FnMut(&Theme) + 'static) -> ScopeValue {
+        return self.0.listeners.listen(cb);
+    }
+}
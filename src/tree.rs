@@ -0,0 +1,213 @@
+use crate::{
+    el,
+    ContainerEntry,
+    El,
+};
+
+struct TreeNode<T> {
+    entry: T,
+    el: El,
+    children_el: El,
+    children: Vec<TreeNode<T>>,
+    expanded: bool,
+    loaded: bool,
+}
+
+type LoadChildren<T> = Box<dyn Fn(&T) -> Vec<T>>;
+
+/// A tree of entries mirrored to nested lists: each node is an `<li>` containing the
+/// entry's element followed by a `<ul>` of its children, shown when the node is
+/// expanded.  Nodes are addressed by paths of indices from the top level.
+///
+/// Children can be added explicitly or loaded lazily by the `load_children` callback
+/// the first time a node without children is expanded.  Per-node state can be
+/// attached to the node's element (see `node_el`).
+pub struct TreeContainer<T: ContainerEntry> {
+    el: El,
+    roots: Vec<TreeNode<T>>,
+    load_children: Option<LoadChildren<T>>,
+}
+
+impl<T: ContainerEntry> TreeContainer<T> {
+    /// `el` should be a `<ul>` (or similar).
+    pub fn new(el: El) -> Self {
+        return TreeContainer {
+            el: el.attr("role", "tree"),
+            roots: vec![],
+            load_children: None,
+        };
+    }
+
+    /// Set a callback to produce a node's children the first time it's expanded.
+    pub fn lazy(mut self, load_children: impl Fn(&T) -> Vec<T> + 'static) -> Self {
+        self.load_children = Some(Box::new(load_children));
+        return self;
+    }
+
+    fn build_node(entry: T) -> TreeNode<T> {
+        let children_el = el("ul").attr("role", "group").attr("hidden", "");
+        return TreeNode {
+            el: el("li")
+                .attr("role", "treeitem")
+                .attr("aria-expanded", "false")
+                .push(entry.el().clone())
+                .push(children_el.clone()),
+            entry,
+            children_el,
+            children: vec![],
+            expanded: false,
+            loaded: false,
+        };
+    }
+
+    fn node(&self, path: &[usize]) -> Option<&TreeNode<T>> {
+        let (first, rest) = path.split_first()?;
+        let mut node = self.roots.get(*first)?;
+        for i in rest {
+            node = node.children.get(*i)?;
+        }
+        return Some(node);
+    }
+
+    fn node_mut(&mut self, path: &[usize]) -> Option<&mut TreeNode<T>> {
+        let (first, rest) = path.split_first()?;
+        let mut node = self.roots.get_mut(*first)?;
+        for i in rest {
+            node = node.children.get_mut(*i)?;
+        }
+        return Some(node);
+    }
+
+    /// The list element and nodes of a node's children, or the top level for an empty
+    /// path.
+    fn level_mut(&mut self, parent: &[usize]) -> Option<(El, &mut Vec<TreeNode<T>>)> {
+        if parent.is_empty() {
+            return Some((self.el.clone(), &mut self.roots));
+        }
+        let node = self.node_mut(parent)?;
+        return Some((node.children_el.clone(), &mut node.children));
+    }
+
+    /// Children added explicitly replace lazy loading for the node.
+    fn mark_loaded(&mut self, parent: &[usize]) {
+        if let Some(node) = self.node_mut(parent) {
+            node.loaded = true;
+        }
+    }
+
+    /// Add an entry as the last child of the node at `parent` (or at the top level if
+    /// `parent` is empty).  Does nothing if `parent` doesn't exist.  Adding a child
+    /// turns off lazy loading for the parent.
+    pub fn push(&mut self, parent: &[usize], entry: T) {
+        let Some((list, nodes)) = self.level_mut(parent) else {
+            return;
+        };
+        let node = Self::build_node(entry);
+        list.ref_push(node.el.clone());
+        nodes.push(node);
+        self.mark_loaded(parent);
+    }
+
+    /// Insert an entry so that it ends up at `path`.  Does nothing if the parent
+    /// doesn't exist or the final index is past the end of its children.  Like
+    /// `push`, this turns off lazy loading for the parent.
+    pub fn insert(&mut self, path: &[usize], entry: T) {
+        let Some((i, parent)) = path.split_last() else {
+            return;
+        };
+        let Some((list, nodes)) = self.level_mut(parent) else {
+            return;
+        };
+        if *i > nodes.len() {
+            return;
+        }
+        let node = Self::build_node(entry);
+        list.ref_splice(*i, 0, vec![node.el.clone()]);
+        nodes.insert(*i, node);
+        self.mark_loaded(parent);
+    }
+
+    /// Remove the node at `path` along with its children, returning its entry.
+    pub fn remove(&mut self, path: &[usize]) -> Option<T> {
+        let (i, parent) = path.split_last()?;
+        let (list, nodes) = self.level_mut(parent)?;
+        if *i >= nodes.len() {
+            return None;
+        }
+        list.ref_splice(*i, 1, vec![]);
+        return Some(nodes.remove(*i).entry);
+    }
+
+    pub fn clear(&mut self) {
+        self.el.ref_clear();
+        self.roots.clear();
+    }
+
+    pub fn get(&self, path: &[usize]) -> Option<&T> {
+        return self.node(path).map(|n| &n.entry);
+    }
+
+    pub fn get_mut(&mut self, path: &[usize]) -> Option<&mut T> {
+        return self.node_mut(path).map(|n| &mut n.entry);
+    }
+
+    /// The `<li>` element for the node, for attaching per-node state with `.own()`.
+    pub fn node_el(&self, path: &[usize]) -> Option<&El> {
+        return self.node(path).map(|n| &n.el);
+    }
+
+    /// The number of children of the node (or top level nodes if `path` is empty).
+    pub fn children_len(&self, path: &[usize]) -> usize {
+        if path.is_empty() {
+            return self.roots.len();
+        }
+        return self.node(path).map(|n| n.children.len()).unwrap_or(0);
+    }
+
+    pub fn is_expanded(&self, path: &[usize]) -> bool {
+        return self.node(path).map(|n| n.expanded).unwrap_or(false);
+    }
+
+    /// Show the node's children, loading them first if necessary (see `lazy`).
+    pub fn expand(&mut self, path: &[usize]) {
+        let load_children = self.load_children.take();
+        if let Some(node) = self.node_mut(path) {
+            if !node.loaded {
+                node.loaded = true;
+                if let Some(load_children) = &load_children {
+                    let nodes = load_children(&node.entry).into_iter().map(Self::build_node).collect::<Vec<_>>();
+                    node.children_el.ref_extend(nodes.iter().map(|n| n.el.clone()).collect());
+                    node.children.extend(nodes);
+                }
+            }
+            node.expanded = true;
+            node.children_el.ref_remove_attr("hidden");
+            node.el.ref_attr("aria-expanded", "true");
+        }
+        self.load_children = load_children;
+    }
+
+    /// Hide the node's children.  The children are kept.
+    pub fn collapse(&mut self, path: &[usize]) {
+        let Some(node) = self.node_mut(path) else {
+            return;
+        };
+        node.expanded = false;
+        node.children_el.ref_attr("hidden", "");
+        node.el.ref_attr("aria-expanded", "false");
+    }
+
+    pub fn toggle(&mut self, path: &[usize]) {
+        if self.is_expanded(path) {
+            self.collapse(path);
+        } else {
+            self.expand(path);
+        }
+    }
+}
+
+impl<T: ContainerEntry> ContainerEntry for TreeContainer<T> {
+    fn el(&self) -> &El {
+        return &self.el;
+    }
+}
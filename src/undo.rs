@@ -0,0 +1,179 @@
+use std::{
+    cell::RefCell,
+    rc::{
+        Rc,
+        Weak,
+    },
+};
+use crate::{
+    Listeners,
+    ScopeValue,
+    ShortcutError,
+    Shortcuts,
+};
+
+/// Whether undo and redo are currently possible, see `UndoStack::subscribe`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct UndoState {
+    pub can_undo: bool,
+    pub can_redo: bool,
+}
+
+struct Command {
+    apply: Box<dyn FnMut()>,
+    revert: Box<dyn FnMut()>,
+}
+
+struct UndoStack_ {
+    done: Vec<Command>,
+    undone: Vec<Command>,
+    limit: Option<usize>,
+}
+
+/// An undo/redo history of commands, each a pair of closures that apply and revert
+/// a change.  The stack is clonable so it can be shared with event handlers; attach
+/// it to the editor element with `.own()` so the history (and anything captured by
+/// the commands) is dropped with the editor.
+#[derive(Clone)]
+pub struct UndoStack {
+    inner: Rc<RefCell<UndoStack_>>,
+    listeners: Listeners<UndoState>,
+}
+
+impl Default for UndoStack {
+    fn default() -> Self {
+        return Self::new();
+    }
+}
+
+impl UndoStack {
+    pub fn new() -> UndoStack {
+        return UndoStack {
+            inner: Rc::new(RefCell::new(UndoStack_ {
+                done: vec![],
+                undone: vec![],
+                limit: None,
+            })),
+            listeners: Listeners::new(),
+        };
+    }
+
+    /// Keep at most `limit` commands, forgetting the oldest.
+    pub fn with_limit(self, limit: usize) -> UndoStack {
+        self.inner.borrow_mut().limit = Some(limit);
+        return self;
+    }
+
+    fn notify(&self) {
+        self.listeners.emit(&self.state());
+    }
+
+    pub fn state(&self) -> UndoState {
+        let inner = self.inner.borrow();
+        return UndoState {
+            can_undo: !inner.done.is_empty(),
+            can_redo: !inner.undone.is_empty(),
+        };
+    }
+
+    /// Call `cb` with the new state after each change to the history, until the
+    /// returned value is dropped.
+    pub fn subscribe(&self, cb: impl FnMut(&UndoState) + 'static) -> ScopeValue {
+        return self.listeners.listen(cb);
+    }
+
+    /// Run `apply` and add the command to the history.  This clears the redo
+    /// history.
+    pub fn push(&self, mut apply: impl FnMut() + 'static, revert: impl FnMut() + 'static) {
+        apply();
+        self.record(apply, revert);
+    }
+
+    /// Add a command whose change has already been made to the history.  This clears
+    /// the redo history.
+    pub fn record(&self, apply: impl FnMut() + 'static, revert: impl FnMut() + 'static) {
+        let dropped = {
+            let mut inner = self.inner.borrow_mut();
+            inner.done.push(Command {
+                apply: Box::new(apply),
+                revert: Box::new(revert),
+            });
+            let mut dropped = inner.undone.drain(..).collect::<Vec<_>>();
+            if let Some(limit) = inner.limit {
+                let excess = inner.done.len().saturating_sub(limit);
+                dropped.extend(inner.done.drain(.. excess));
+            }
+            dropped
+        };
+        drop(dropped);
+        self.notify();
+    }
+
+    /// Revert the most recent command, returning whether there was one.
+    pub fn undo(&self) -> bool {
+        let Some(mut command) = self.inner.borrow_mut().done.pop() else {
+            return false;
+        };
+        (command.revert)();
+        self.inner.borrow_mut().undone.push(command);
+        self.notify();
+        return true;
+    }
+
+    /// Reapply the most recently undone command, returning whether there was one.
+    pub fn redo(&self) -> bool {
+        let Some(mut command) = self.inner.borrow_mut().undone.pop() else {
+            return false;
+        };
+        (command.apply)();
+        self.inner.borrow_mut().done.push(command);
+        self.notify();
+        return true;
+    }
+
+    /// Forget all history.
+    pub fn clear(&self) {
+        let dropped = {
+            let mut inner = self.inner.borrow_mut();
+            (inner.done.drain(..).collect::<Vec<_>>(), inner.undone.drain(..).collect::<Vec<_>>())
+        };
+        drop(dropped);
+        self.notify();
+    }
+
+    fn weak(&self) -> (Weak<RefCell<UndoStack_>>, Listeners<UndoState>) {
+        return (Rc::downgrade(&self.inner), self.listeners.clone());
+    }
+
+    /// Bind `Mod+z` to undo and `Mod+Shift+z` and `Mod+y` to redo.  The bindings
+    /// don't keep the stack alive.  If any of the shortcuts is already bound, returns
+    /// an error without binding any of them.
+    pub fn bind_shortcuts(&self, shortcuts: &Shortcuts) -> Result<(), ShortcutError> {
+        for chord in ["Mod+z", "Mod+Shift+z", "Mod+y"] {
+            if shortcuts.is_bound(chord)? {
+                return Err(ShortcutError::Conflict(chord.to_string()));
+            }
+        }
+        let upgrade = |(inner, listeners): &(Weak<RefCell<UndoStack_>>, Listeners<UndoState>)| {
+            return Some(UndoStack {
+                inner: inner.upgrade()?,
+                listeners: listeners.clone(),
+            });
+        };
+        let weak = self.weak();
+        shortcuts.bind("Mod+z", move |_| {
+            if let Some(s) = upgrade(&weak) {
+                s.undo();
+            }
+        })?;
+        for chord in ["Mod+Shift+z", "Mod+y"] {
+            let weak = self.weak();
+            shortcuts.bind(chord, move |_| {
+                if let Some(s) = upgrade(&weak) {
+                    s.redo();
+                }
+            })?;
+        }
+        return Ok(());
+    }
+}
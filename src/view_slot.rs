@@ -0,0 +1,227 @@
+use std::{
+    cell::RefCell,
+    rc::{
+        Rc,
+        Weak,
+    },
+};
+use gloo_events::EventListener;
+use js_sys::{
+    Array,
+    Function,
+    Object,
+    Reflect,
+};
+use wasm_bindgen::{
+    JsCast,
+    JsValue,
+};
+use web_sys::Element;
+use crate::{
+    schedule::animation_frame,
+    scope_any,
+    ContainerEntry,
+    El,
+    ScopeValue,
+    WeakEl,
+};
+
+/// The number of css animations and transitions running on the element or its
+/// descendants.
+fn running_animations(el: &Element) -> usize {
+    let Some(get) = Reflect::get(el, &JsValue::from_str("getAnimations")).ok().and_then(|f| f.dyn_into::<Function>().ok()) else {
+        return 0;
+    };
+    let opts = Object::new();
+    _ = Reflect::set(&opts, &JsValue::from_str("subtree"), &JsValue::TRUE);
+    let Ok(animations) = get.call1(el, &opts) else {
+        return 0;
+    };
+    return Array::from(&animations)
+        .iter()
+        .filter(
+            |a| Reflect::get(a, &JsValue::from_str("playState")).ok().and_then(|s| s.as_string()).as_deref() ==
+                Some("running"),
+        )
+        .count();
+}
+
+/// Call `cb` once no animations or transitions are running in the element, checking
+/// on the next frame and after each animation/transition ends.
+fn when_animations_done(el: &Element, cb: impl FnOnce() + 'static) -> ScopeValue {
+    let cb = Rc::new(RefCell::new(Some(Box::new(cb) as Box<dyn FnOnce()>)));
+    let check = Rc::new({
+        let el = el.clone();
+        move || {
+            if running_animations(&el) > 0 {
+                return;
+            }
+            let Some(cb) = cb.borrow_mut().take() else {
+                return;
+            };
+            cb();
+        }
+    });
+    let mut own = vec![];
+    for event in ["animationend", "animationcancel", "transitionend", "transitioncancel"] {
+        own.push(scope_any(EventListener::new(el, event, {
+            let check = check.clone();
+            move |_| check()
+        })));
+    }
+    own.push(animation_frame(move |_| check()));
+    return scope_any(own);
+}
+
+struct Transitioning {
+    el: El,
+    class: String,
+    _done: ScopeValue,
+}
+
+#[derive(Default)]
+struct ViewSlotState {
+    current: Option<El>,
+    entering: Option<Transitioning>,
+    leaving: Vec<Transitioning>,
+}
+
+/// Holds a single view in an element, swapping it out for another on request, with
+/// optional css enter/exit transitions (ex: for routing).
+///
+/// During a transition the outgoing and incoming elements are both children of the
+/// slot element, so you'll need to style them to overlap (ex: placing them in the
+/// same `grid-area`).
+pub struct ViewSlot {
+    el: El,
+    state: Rc<RefCell<ViewSlotState>>,
+}
+
+impl ViewSlot {
+    /// `el` is the element views are placed in.  Any existing children are left as
+    /// is.
+    pub fn new(el: El) -> Self {
+        let state = Rc::new(RefCell::new(ViewSlotState::default()));
+        el.ref_own(|_| state.clone());
+        return ViewSlot {
+            el,
+            state,
+        };
+    }
+
+    /// The current (or incoming, during a transition) view.
+    pub fn current(&self) -> Option<El> {
+        return self.state.borrow().current.clone();
+    }
+
+    /// Replace the current view with `view` immediately.  Any transition in progress
+    /// is finished immediately.
+    pub fn set(&self, view: El) {
+        self.finish_transitions();
+        let old = self.state.borrow_mut().current.replace(view.clone());
+        self.remove_view(old);
+        self.el.ref_push(view);
+    }
+
+    /// Remove the current view, if any.  Any transition in progress is finished
+    /// immediately.
+    pub fn clear(&self) {
+        self.finish_transitions();
+        let old = self.state.borrow_mut().current.take();
+        self.remove_view(old);
+    }
+
+    /// Replace the current view with `view`, adding `exit_class` to the outgoing view
+    /// and `enter_class` to the incoming view.  The outgoing view is dropped and
+    /// `enter_class` is removed once their css animations/transitions finish (or
+    /// immediately if the class doesn't start any).
+    ///
+    /// Use css animations (keyframes) for `enter_class` - the incoming view is
+    /// inserted with the class already applied so transitions won't run.
+    ///
+    /// If another view is set before this transition finishes, this transition is
+    /// finished immediately.
+    pub fn set_with_transition(&self, view: El, exit_class: &str, enter_class: &str) {
+        self.finish_transitions();
+        let old = self.state.borrow_mut().current.replace(view.clone());
+        let weak_state = Rc::downgrade(&self.state);
+        if let Some(old) = old {
+            let old_raw = old.raw();
+            _ = old_raw.class_list().add_1(exit_class);
+            let done = when_animations_done(&old_raw, {
+                let state = weak_state.clone();
+                let slot = self.el.weak();
+                let id = old.ptr_id();
+                move || finish_leaving(&state, &slot, id)
+            });
+            self.state.borrow_mut().leaving.push(Transitioning {
+                el: old,
+                class: exit_class.to_string(),
+                _done: done,
+            });
+        }
+        let view_raw = view.raw();
+        _ = view_raw.class_list().add_1(enter_class);
+        let done = when_animations_done(&view_raw, move || {
+            let Some(state) = weak_state.upgrade() else {
+                return;
+            };
+            let entering = state.borrow_mut().entering.take();
+            if let Some(entering) = entering {
+                _ = entering.el.raw().class_list().remove_1(&entering.class);
+            }
+        });
+        self.state.borrow_mut().entering = Some(Transitioning {
+            el: view.clone(),
+            class: enter_class.to_string(),
+            _done: done,
+        });
+        self.el.ref_push(view);
+    }
+
+    /// Jump any in-progress transitions to the end.
+    fn finish_transitions(&self) {
+        let (entering, leaving) = {
+            let mut s = self.state.borrow_mut();
+            (s.entering.take(), s.leaving.split_off(0))
+        };
+        if let Some(entering) = entering {
+            _ = entering.el.raw().class_list().remove_1(&entering.class);
+        }
+        for leaving in leaving {
+            _ = leaving.el.raw().class_list().remove_1(&leaving.class);
+            self.remove_view(Some(leaving.el));
+        }
+    }
+
+    fn remove_view(&self, view: Option<El>) {
+        let Some(view) = view else {
+            return;
+        };
+        self.el.ref_remove_child(&view);
+    }
+}
+
+fn finish_leaving(state: &Weak<RefCell<ViewSlotState>>, slot: &WeakEl, id: usize) {
+    let Some(state) = state.upgrade() else {
+        return;
+    };
+    let Some(slot) = slot.upgrade() else {
+        return;
+    };
+    let leaving = {
+        let mut s = state.borrow_mut();
+        let Some(i) = s.leaving.iter().position(|l| l.el.ptr_id() == id) else {
+            return;
+        };
+        s.leaving.remove(i)
+    };
+    _ = leaving.el.raw().class_list().remove_1(&leaving.class);
+    slot.ref_remove_child(&leaving.el);
+}
+
+impl ContainerEntry for ViewSlot {
+    fn el(&self) -> &El {
+        return &self.el;
+    }
+}
@@ -0,0 +1,109 @@
+use std::{
+    cell::RefCell,
+    future::Future,
+    rc::{
+        Rc,
+        Weak,
+    },
+};
+use gloo_utils::window;
+use js_sys::{
+    Function,
+    Promise,
+    Reflect,
+};
+use wasm_bindgen::{
+    JsCast,
+    JsValue,
+};
+use wasm_bindgen_futures::JsFuture;
+use crate::{
+    lifecycle::on_visibility_change,
+    spawn_rooted,
+    ScopeValue,
+};
+
+fn call_method(target: &JsValue, name: &str, args: &[JsValue]) -> Result<JsValue, JsValue> {
+    let f = Reflect::get(target, &JsValue::from_str(name))?.dyn_into::<Function>()?;
+    return Reflect::apply(&f, target, &args.iter().collect());
+}
+
+/// Request a screen wake lock, returning the sentinel.
+async fn request_sentinel() -> Result<JsValue, JsValue> {
+    let wake_lock = Reflect::get(&window().navigator(), &JsValue::from_str("wakeLock"))?;
+    if wake_lock.is_undefined() {
+        return Err(JsValue::from_str("Wake lock API is not supported"));
+    }
+    let promise = call_method(&wake_lock, "request", &[JsValue::from_str("screen")])?.dyn_into::<Promise>()?;
+    return JsFuture::from(promise).await;
+}
+
+struct WakeLockGuard_ {
+    sentinel: RefCell<Option<JsValue>>,
+    reacquire: RefCell<Option<ScopeValue>>,
+    visibility: RefCell<Option<ScopeValue>>,
+}
+
+impl Drop for WakeLockGuard_ {
+    fn drop(&mut self) {
+        if let Some(sentinel) = self.sentinel.take() {
+            _ = call_method(&sentinel, "release", &[]);
+        }
+    }
+}
+
+/// Keeps the screen from dimming or locking while alive.  Browsers release wake
+/// locks when the page is hidden; the guard requests the lock again each time the
+/// page becomes visible.
+pub struct WakeLockGuard(Rc<WakeLockGuard_>);
+
+impl WakeLockGuard {
+    /// Whether the lock is currently held.  This is false if the request failed (ex:
+    /// not supported, or the page isn't visible).
+    pub fn is_held(&self) -> bool {
+        return self.0.sentinel.borrow().is_some();
+    }
+}
+
+fn reacquire(inner: &Weak<WakeLockGuard_>) -> ScopeValue {
+    let inner = inner.clone();
+    return spawn_rooted(async move {
+        let sentinel = request_sentinel().await.ok();
+        let Some(inner) = inner.upgrade() else {
+            return;
+        };
+        let old = inner.sentinel.replace(sentinel);
+        if let Some(old) = old {
+            _ = call_method(&old, "release", &[]);
+        }
+    });
+}
+
+/// Acquire a screen wake lock.  The guard is returned even if the lock couldn't be
+/// acquired, since it'll be retried when the page next becomes visible; check
+/// `WakeLockGuard::is_held` if it matters.
+pub fn acquire_wake_lock() -> impl Future<Output = WakeLockGuard> {
+    return async move {
+        let inner = Rc::new(WakeLockGuard_ {
+            sentinel: RefCell::new(request_sentinel().await.ok()),
+            reacquire: RefCell::new(None),
+            visibility: RefCell::new(None),
+        });
+        *inner.visibility.borrow_mut() = Some(on_visibility_change({
+            let inner = Rc::downgrade(&inner);
+            move |visible| {
+                let Some(inner1) = inner.upgrade() else {
+                    return;
+                };
+                if visible {
+                    let task = reacquire(&inner);
+                    *inner1.reacquire.borrow_mut() = Some(task);
+                } else {
+                    // Released by the browser
+                    *inner1.sentinel.borrow_mut() = None;
+                }
+            }
+        }));
+        return WakeLockGuard(inner);
+    };
+}
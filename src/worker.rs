@@ -0,0 +1,168 @@
+use std::{
+    cell::{
+        Cell,
+        RefCell,
+    },
+    collections::HashMap,
+    marker::PhantomData,
+    rc::Rc,
+};
+use futures::{
+    channel::oneshot,
+    Future,
+};
+use gloo_events::EventListener;
+use js_sys::{
+    Array,
+    Object,
+    Reflect,
+};
+use serde::{
+    de::DeserializeOwned,
+    Serialize,
+};
+use wasm_bindgen::{
+    JsCast,
+    JsValue,
+};
+use web_sys::{
+    Blob,
+    BlobPropertyBag,
+    MessageEvent,
+    Url,
+    Worker,
+};
+
+/// Where to load the worker script from.
+pub enum WorkerSource<'a> {
+    /// A url to load the script from.
+    Url(&'a str),
+    /// The javascript source of the worker.
+    Inline(&'a str),
+}
+
+type Responder = oneshot::Sender<Result<JsValue, JsValue>>;
+
+struct WorkerHandle_ {
+    worker: Worker,
+    object_url: Option<String>,
+    next_id: Cell<u32>,
+    pending: Rc<RefCell<HashMap<u32, Responder>>>,
+    _message_listener: EventListener,
+    _error_listener: EventListener,
+}
+
+impl Drop for WorkerHandle_ {
+    fn drop(&mut self) {
+        self.worker.terminate();
+        if let Some(url) = &self.object_url {
+            _ = Url::revoke_object_url(url);
+        }
+    }
+}
+
+/// A web worker that's terminated when the handle is dropped.  Attach it to an
+/// element with `.own()` to tie the worker's lifetime to the element.
+///
+/// Requests are sent to the worker as `{id, data}` messages, where `data` is the
+/// serialized input.  The worker must respond to each with `{id, data}` where
+/// `data` is the serialized output, or `{id, error}` to fail the request.
+pub struct WorkerHandle<In: Serialize, Out: DeserializeOwned> {
+    inner: Rc<WorkerHandle_>,
+    _types: PhantomData<(In, Out)>,
+}
+
+fn terminated() -> JsValue {
+    return JsValue::from_str("Worker terminated");
+}
+
+/// Start a worker. See `WorkerHandle` for the message protocol.
+pub fn spawn_worker<
+    In: Serialize,
+    Out: DeserializeOwned,
+>(source: WorkerSource) -> Result<WorkerHandle<In, Out>, JsValue> {
+    let (url, object_url) = match source {
+        WorkerSource::Url(url) => (url.to_string(), None),
+        WorkerSource::Inline(script) => {
+            let opts = BlobPropertyBag::new();
+            opts.set_type("application/javascript");
+            let blob = Blob::new_with_str_sequence_and_options(&Array::of1(&JsValue::from_str(script)), &opts)?;
+            let url = Url::create_object_url_with_blob(&blob)?;
+            (url.clone(), Some(url))
+        },
+    };
+    let worker = Worker::new(&url)?;
+    let pending = Rc::new(RefCell::new(HashMap::<u32, Responder>::new()));
+    let message_listener = EventListener::new(&worker, "message", {
+        let pending = pending.clone();
+        move |ev| {
+            let data = ev.dyn_ref::<MessageEvent>().unwrap().data();
+            let Some(id) = Reflect::get(&data, &JsValue::from_str("id")).ok().and_then(|id| id.as_f64()) else {
+                return;
+            };
+            let Some(respond) = pending.borrow_mut().remove(&(id as u32)) else {
+                return;
+            };
+            let error = Reflect::get(&data, &JsValue::from_str("error")).unwrap_or(JsValue::UNDEFINED);
+            if !error.is_undefined() {
+                _ = respond.send(Err(error));
+            } else {
+                _ = respond.send(Ok(Reflect::get(&data, &JsValue::from_str("data")).unwrap_or(JsValue::UNDEFINED)));
+            }
+        }
+    });
+    let error_listener = EventListener::new(&worker, "error", {
+        let pending = pending.clone();
+        move |ev| {
+            let error: JsValue = ev.clone().into();
+            for (_, respond) in pending.borrow_mut().drain() {
+                _ = respond.send(Err(error.clone()));
+            }
+        }
+    });
+    return Ok(WorkerHandle {
+        inner: Rc::new(WorkerHandle_ {
+            worker,
+            object_url,
+            next_id: Cell::new(0),
+            pending,
+            _message_listener: message_listener,
+            _error_listener: error_listener,
+        }),
+        _types: PhantomData,
+    });
+}
+
+impl<In: Serialize, Out: DeserializeOwned> WorkerHandle<In, Out> {
+    /// Send a request to the worker and wait for the response.  If the handle is
+    /// dropped before the response arrives the future resolves to an error.
+    pub fn request(&self, input: &In) -> impl Future<Output = Result<Out, JsValue>> {
+        let (tx, rx) = oneshot::channel();
+        let id = self.inner.next_id.get();
+        self.inner.next_id.set(id.wrapping_add(1));
+        let sent = (|| -> Result<(), JsValue> {
+            let message = Object::new();
+            Reflect::set(&message, &JsValue::from_str("id"), &JsValue::from(id))?;
+            Reflect::set(&message, &JsValue::from_str("data"), &serde_wasm_bindgen::to_value(input)?)?;
+            self.inner.worker.post_message(&message)?;
+            return Ok(());
+        })();
+        match sent {
+            Ok(_) => {
+                self.inner.pending.borrow_mut().insert(id, tx);
+            },
+            Err(e) => {
+                _ = tx.send(Err(e));
+            },
+        }
+        return async move {
+            let data = rx.await.map_err(|_| terminated())??;
+            return Ok(serde_wasm_bindgen::from_value(data)?);
+        };
+    }
+
+    /// The raw `web_sys` worker, for sending messages outside the request protocol.
+    pub fn raw(&self) -> Worker {
+        return self.inner.worker.clone();
+    }
+}